@@ -1,69 +1,690 @@
 use std::collections::HashMap;
-use crate::evaluator::{AST, ASTNode, Evaluator};
-use crate::evaluator::tokenizer::{Token, TokenType, Tokenizer, checkSyntax, mapToBool, parseBlocks};
+use crate::evaluator::{normalform, profile, solver, AST, ASTNode, EvaluationError, Evaluator, Operator};
+use crate::evaluator::diagnostic::SyntaxError;
+use crate::evaluator::parser::Parser;
+use crate::evaluator::tokenizer::{Associativity, Notation, Token, TokenType, Tokenizer, expandRange, getIdentifiers, getNotation, maxRange, setNotation};
 
-pub fn commandFinder(line: &Vec<Token>) -> String {
+const maxTableVariables: usize = 12;
+
+struct Formula {
+    text: String,
+    identifiers: Vec<String>,
+    root: ASTNode,
+    options: Vec<String>
+}
+
+pub fn commandFinder(tokenizer: &Tokenizer) -> String {
+    let line = &tokenizer.tokens;
     if line.is_empty() {
         return "Es wurde nichts übergeben!".to_string();
     }
+    // Mit default oder engineering am Ende wird die Schreibweise nur für diesen Befehl gewechselt
+    if let Some((index, notation)) = findNotationOption(line) {
+        let mut retokenized = Tokenizer::withNotation(tokenizer.line.clone(), notation);
+        retokenized.tokenize();
+        retokenized.tokens.remove(index);
+        return commandFinder(&retokenized);
+    }
+    let errors = findSyntaxErrors(tokenizer);
+    if !errors.is_empty() {
+        return renderSyntaxErrors(&tokenizer.line, &errors);
+    }
     match line[0].tType {
         TokenType::EXPRESSION => { return commandEvaluate(line) },
         TokenType::COMMAND => { 
-            if line[0].content == "table".to_string() { return commandTable(line); }
-            else { return "".to_string(); }
+            match line[0].content.as_str() {
+                "table" => { return commandTable(line); },
+                "valid" => { return commandValid(line); },
+                "satis" => { return commandSatis(line); },
+                "semcons" => { return commandSemcons(line); },
+                "cnf" => { return commandNormalForm(line, true); },
+                "dnf" => { return commandNormalForm(line, false); },
+                "latex" => { return commandLatex(line); },
+                "tree" => { return commandTree(line); },
+                "profile" => { return commandProfile(line); },
+                "notation" => { return commandNotation(line); },
+                _ => { return "".to_string(); }
+            }
         },
         _ => { return "Das ist ein unbekannter Befehl. Nutze einen der gegebenen".to_string(); }
     }
 }
 
 pub fn commandHelp() -> String {
-    return "Diese Terminal-Applikation soll zum evaluieren von booleschen Formeln dienen. Zudem gibt es nützliche Befehle, welche z.B. die konjunktive oder disjunktive Normalform einer Formel wiedergeben. \r\nWird in einem Befehl nach <AUSDRUCK> gefragt, handelt es sich hierbei, um eine boolesche Formel innerhalb von Rechtecksklammern (also die hier: [])\r\n\nBEFEHLE:\r\t\n<AUSDRUCK> <VARIABLE1> <0/1> <VARIABLE2> <0/1> ... <VARIABLEn> <0/1>                 Evaluiert den gegebenen Ausdruck mit den gegebenen Variablenbelegungen\r\t\ntable <AUSDRUCK>                                                                     Gibt die Wahrheitswertetabelle der Formel wieder".to_string()
+    return "Diese Terminal-Applikation soll zum evaluieren von booleschen Formeln dienen. Zudem gibt es nützliche Befehle, welche z.B. die konjunktive oder disjunktive Normalform einer Formel wiedergeben. \r\nWird in einem Befehl nach <AUSDRUCK> gefragt, handelt es sich hierbei, um eine boolesche Formel innerhalb von Rechtecksklammern (also die hier: []). Statt ! && || -> <- <-> ^ !&& !|| 1 0 gehen auch ¬ ∧ ∨ → ← ↔ ⊕ ↑ ↓ ⊤ ⊥, true false T F sowie not and or implies iff xor nand nor, nicht und oder und wenn ... dann ...\r\n\nBEFEHLE:\r\t\n<AUSDRUCK> <VARIABLE1> <0/1> <VARIABLE2> <0/1> ... <VARIABLEn> <0/1>                 Evaluiert den gegebenen Ausdruck mit den gegebenen Variablenbelegungen, x[1..4] <0/1> belegt x[1] bis x[4]. Fehlen Belegungen, wird die vereinfachte Restformel ausgegeben\r\t\ntable <AUSDRUCK> [steps]                                                             Gibt die Wahrheitswertetabelle der Formel wieder, mit steps auch für jede Teilformel\r\t\nvalid <AUSDRUCK>                                                                     Prüft, ob die Formel allgemeingültig ist, und gibt sonst ein Gegenbeispiel wieder\r\t\nsatis <AUSDRUCK> [all/count]                                                         Prüft, ob die Formel erfüllbar ist, und gibt eine erfüllende Belegung, alle Modelle (all) oder deren Anzahl (count) wieder\r\t\nsemcons <AUSDRUCK1> ... <AUSDRUCKn> <AUSDRUCK>                                       Prüft, ob der letzte Ausdruck aus den vorherigen Ausdrücken semantisch folgt, und gibt sonst ein Gegenbeispiel wieder\r\t\ncnf <AUSDRUCK> [rewrite/tseitin]                                                     Gibt die kanonische konjunktive Normalform (KNF) der Formel wieder, mit rewrite durch Umformen und mit tseitin eine erfüllbarkeitsäquivalente KNF\r\t\ndnf <AUSDRUCK>                                                                       Gibt die kanonische disjunktive Normalform (DNF) der Formel wieder\r\t\nlatex <AUSDRUCK>                                                                     Gibt die Formel als LaTeX wieder\r\t\ntree <AUSDRUCK>                                                                      Zeichnet den Syntaxbaum der Formel\r\t
+profile [default/strict/custom <STUFE1> [right] ... <STUFEn> [right]]                Zeigt oder wechselt das Profil für Präzedenz und Assoziativität, bei custom ist jede Stufe ein Ausdruck aus Operatoren wie [&& ^], left/right hinter einem Operator wie in [-> right <-] gilt nur für diesen\r\t
+notation [default/engineering]                                                       Zeigt oder wechselt die Schreibweise, mit engineering ist ab' + c(d + e)' dasselbe wie [a && !b || c && !(d || e)]. Hinter einem Befehl gilt default/engineering nur für diesen".to_string()
 }
 
 fn commandEvaluate(line: &Vec<Token>) -> String {
-    let mut idToBool: HashMap<String, String> = HashMap::new();
-    let mut i: u16 = 1;
+    let mut idToBool: HashMap<String, bool> = HashMap::new();
+    let mut i: usize = 1;
     let expr = line[0].clone();
 
-    while i < (line.len() as u16) {
-        if line[i as usize].tType == TokenType::EOL {
+    while i < line.len() {
+        if line[i].tType == TokenType::EOL {
             break;
         } 
-        if (i+1) == (line.len() as u16) {
-            return format!("Es ist ein Fehler an der Stelle {} aufgetreten. Vielleicht fehlt hier ein boolean für die korrespondierende Variable", i);
+        if (i+1) == line.len() {
+            return format!("Es ist ein Fehler an der Stelle {} aufgetreten. Vielleicht fehlt hier ein boolean für die korrespondierende Variable", line[i].start);
             
         } 
-        if line[i as usize].tType == TokenType::IDENTIFIER && line[(i+1) as usize].tType == TokenType::BOOL {
-           idToBool.insert(line[i as usize].content.clone(), line[(i+1) as usize].content.clone());
+        if line[i].tType == TokenType::IDENTIFIER && line[i+1].tType == TokenType::BOOL {
+           idToBool.insert(line[i].content.clone(), line[i+1].content == "1");
            i += 2;
         }
+        else if line[i].tType == TokenType::RANGE && line[i+1].tType == TokenType::BOOL {
+            // x[1..3] 1 belegt alle Elemente des Bereiches
+            let identifiers = match expandRange(&line[i].content) {
+                Some(identifiers) => identifiers,
+                None => { return format!("Der Bereich {} hat mehr als {} Elemente", line[i].content, maxRange); }
+            };
+            for identifier in identifiers {
+                idToBool.insert(identifier, line[i+1].content == "1");
+            }
+            i += 2;
+        }
         else {
             i += 1;
-            return format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). Vielleicht wurde die Reihenfolge von <VARIABLE> <0/1> nicht eingehalten oder es wurde irgendwas unerwartetes gefunden", i, line[i as usize].content.clone());
+            return format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). Vielleicht wurde die Reihenfolge von <VARIABLE> <0/1/true/false> nicht eingehalten oder es wurde irgendwas unerwartetes gefunden", line[i].start, line[i].content.clone());
+        }
+    }
+
+    let root = match buildTree(&expr.expression) {
+        Ok(result) => result,
+        Err(message) => { return message; }
+    };
+    match root.evaluate(&idToBool) {
+        Ok(result) => format!("Ergebnis: {}", boolToString(result)),
+        // Bei einer teilweisen Belegung werden die Werte eingesetzt und die Restformel vereinfacht
+        Err(EvaluationError::UNASSIGNED(_)) => match solver::simplify(&solver::assign(&root, &idToBool)) {
+            ASTNode::CONSTANT(value) => format!("Ergebnis: {} (unabhängig von den nicht belegten Variablen)", boolToString(value)),
+            residual => format!("Restformel: [{}]", residual)
+        },
+        Err(error) => format!("Der Ausdruck konnte nicht evaluiert werden: {}", error)
+    }
+}
+
+fn commandTable(line: &Vec<Token>) -> String {
+    let formula = match getFormula(line, &["steps"]) {
+        Ok(formula) => formula,
+        Err(message) => { return message; }
+    };
+    let steps = formula.options.contains(&"steps".to_string());
+    let identifiers = &formula.identifiers;
+
+    if identifiers.len() > maxTableVariables {
+        return format!("Die Formel hat {} Variablen, die Tabelle wäre mit 2^{} Zeilen zu groß (maximal {} Variablen)", identifiers.len(), identifiers.len(), maxTableVariables);
+    }
+
+    let mut columns: Vec<ASTNode> = vec![];
+    if steps {
+        formula.root.getSubformulas(&mut columns);
+        columns.pop();
+    }
+    columns.push(formula.root.clone());
+
+    let mut header = identifiers.clone();
+    let mut nodes: Vec<ASTNode> = vec![];
+    for (i, node) in columns.iter().enumerate() {
+        let label = if i + 1 == columns.len() { formula.text.clone() } else { node.to_string() };
+        if !header.contains(&label) {
+            header.push(label);
+            nodes.push(node.clone());
+        }
+    }
+
+    let mut rows: Vec<Vec<String>> = vec![];
+    for row in 0..(1usize << identifiers.len()) {
+        let idToBool = getRow(identifiers, row);
+        let mut cells: Vec<String> = identifiers.iter().map(|identifier| boolToString(idToBool[identifier])).collect();
+        for node in &nodes {
+            cells.push(boolToString(node.evaluate(&idToBool).unwrap()));
+        }
+        rows.push(cells);
+    }
+
+    renderTable(&header, &rows)
+}
+
+fn commandValid(line: &Vec<Token>) -> String {
+    let formula = match getFormula(line, &[]) {
+        Ok(formula) => formula,
+        Err(message) => { return message; }
+    };
+
+    match findAssignment(&formula.root, &formula.identifiers, false) {
+        Ok(Some(idToBool)) => format!("Die Formel ist nicht allgemeingültig. Gegenbeispiel: {}", formatAssignment(&formula.identifiers, &idToBool)),
+        Ok(None) => "Die Formel ist allgemeingültig (eine Tautologie)".to_string(),
+        Err(message) => message
+    }
+}
+
+fn commandSatis(line: &Vec<Token>) -> String {
+    let formula = match getFormula(line, &["all", "count"]) {
+        Ok(formula) => formula,
+        Err(message) => { return message; }
+    };
+    let identifiers = &formula.identifiers;
+
+    if formula.options.contains(&"all".to_string()) {
+        if identifiers.len() > maxTableVariables {
+            return format!("Die Formel hat {} Variablen, es können höchstens für {} Variablen alle Modelle aufgelistet werden", identifiers.len(), maxTableVariables);
+        }
+
+        let mut rows: Vec<Vec<String>> = vec![];
+        for row in 0..(1usize << identifiers.len()) {
+            let idToBool = getRow(identifiers, row);
+            if formula.root.evaluate(&idToBool).unwrap() {
+                rows.push(identifiers.iter().map(|identifier| boolToString(idToBool[identifier])).collect());
+            }
+        }
+
+        if rows.is_empty() {
+            return "Die Formel ist unerfüllbar, es gibt keine Modelle".to_string();
+        }
+        if identifiers.is_empty() {
+            return "Die Formel ist erfüllbar, sie enthält keine Variablen".to_string();
+        }
+        return format!("Die Formel hat {} Modelle:\r\n{}", rows.len(), renderTable(identifiers, &rows));
+    }
+
+    if formula.options.contains(&"count".to_string()) {
+        if identifiers.len() > 127 {
+            return format!("Die Formel hat {} Variablen, es können höchstens für 127 Variablen die Modelle gezählt werden", identifiers.len());
+        }
+        let count = if identifiers.len() <= maxTableVariables {
+            (0..(1usize << identifiers.len())).filter(|row| formula.root.evaluate(&getRow(identifiers, *row)).unwrap()).count() as u128
+        } else {
+            match solver::countModels(&formula.root, identifiers.len() as u32) {
+                Ok(count) => count,
+                Err(message) => { return message; }
+            }
+        };
+        return format!("Die Formel hat {} von {} möglichen Belegungen als Modell", count, 1u128 << identifiers.len());
+    }
+
+    match findAssignment(&formula.root, identifiers, true) {
+        Ok(Some(idToBool)) => format!("Die Formel ist erfüllbar. Erfüllende Belegung: {}", formatAssignment(identifiers, &idToBool)),
+        Ok(None) => "Die Formel ist unerfüllbar".to_string(),
+        Err(message) => message
+    }
+}
+
+fn commandSemcons(line: &Vec<Token>) -> String {
+    let mut trees: Vec<ASTNode> = vec![];
+    let mut identifiers: Vec<String> = vec![];
+    let mut i: usize = 1;
+    while line[i].tType != TokenType::EOL {
+        if line[i].tType != TokenType::EXPRESSION {
+            return format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). Es wurden nur <AUSDRUCK>e erwartet", line[i].start, line[i].content);
+        }
+        match buildTree(&line[i].expression) {
+            Ok(root) => trees.push(root),
+            Err(message) => { return message; }
+        }
+        for identifier in getIdentifiers(&line[i].expression) {
+            if !identifiers.contains(&identifier) {
+                identifiers.push(identifier);
+            }
         }
+        i += 1;
+    }
+
+    if trees.is_empty() {
+        return "Der Befehl semcons erwartet mindestens eine Konklusion, z.B. semcons [a] [a -> b] [b]".to_string();
+    }
+
+    // Die Folgerung gilt genau dann, wenn p1 && ... && pn && !c unerfüllbar ist
+    let mut combined = ASTNode::NOT(Box::new(trees.pop().unwrap()));
+    while let Some(premise) = trees.pop() {
+        combined = ASTNode::BINARY(Operator::AND, Box::new(premise), Box::new(combined));
+    }
+
+    match findAssignment(&combined, &identifiers, true) {
+        Ok(Some(idToBool)) => format!("Die Konklusion folgt nicht aus den Prämissen. Gegenbeispiel (alle Prämissen wahr, Konklusion falsch): {}", formatAssignment(&identifiers, &idToBool)),
+        Ok(None) => "Die Konklusion folgt semantisch aus den Prämissen".to_string(),
+        Err(message) => message
     }
+}
+
+// Kanonische Normalformen aus der Wahrheitswertetabelle: die KNF besteht aus einer Klausel je
+// falscher Zeile, die DNF aus einem Minterm je wahrer Zeile
+fn commandNormalForm(line: &Vec<Token>, conjunctive: bool) -> String {
+    let allowed: &[&str] = if conjunctive { &["rewrite", "tseitin"] } else { &[] };
+    let formula = match getFormula(line, allowed) {
+        Ok(formula) => formula,
+        Err(message) => { return message; }
+    };
+    let name = if conjunctive { "KNF" } else { "DNF" };
+    let identifiers = &formula.identifiers;
 
-    if checkSyntax(&expr.expression) {
-        let mut tokens = expr.expression;
-        mapToBool(&mut tokens, idToBool);
-        tokens = parseBlocks(&mut tokens);
+    if formula.options.contains(&"tseitin".to_string()) {
+        let (clauses, auxiliary) = normalform::tseitinCNF(&formula.root, identifiers);
+        if auxiliary.is_empty() {
+            return format!("KNF: [{}]", normalform::formatClauses(&clauses));
+        }
+        return format!("KNF (Tseitin, erfüllbarkeitsäquivalent mit den Hilfsvariablen {}): [{}]", auxiliary.join(", "), normalform::formatClauses(&clauses));
+    }
 
-        let mut ev = Evaluator {
-            expression: tokens.clone(),
-            tree: AST { root: None }
+    if formula.options.contains(&"rewrite".to_string()) {
+        return match normalform::rewriteCNF(&formula.root) {
+            Some(clauses) => format!("KNF: [{}]", normalform::formatClauses(&clauses)),
+            None => format!("Die KNF hätte mehr als {} Klauseln. Nutze cnf <AUSDRUCK> tseitin für eine erfüllbarkeitsäquivalente KNF", normalform::maxClauses)
         };
+    }
+
+    if identifiers.len() > maxTableVariables {
+        if conjunctive {
+            return format!("Die Formel hat {} Variablen, die kanonische KNF kann nur für höchstens {} Variablen aufgestellt werden. Nutze cnf <AUSDRUCK> rewrite oder cnf <AUSDRUCK> tseitin", identifiers.len(), maxTableVariables);
+        }
+        return format!("Die Formel hat {} Variablen, die kanonische {} kann nur für höchstens {} Variablen aufgestellt werden", identifiers.len(), name, maxTableVariables);
+    }
+
+    let (literalOp, termOp) = if conjunctive { (" || ", " && ") } else { (" && ", " || ") };
+    let mut terms: Vec<Vec<String>> = vec![];
+    for row in 0..(1usize << identifiers.len()) {
+        let idToBool = getRow(identifiers, row);
+        if formula.root.evaluate(&idToBool).unwrap() == conjunctive {
+            continue;
+        }
 
-        ev.buildAST();
-        let a = ev.getAST();
+        terms.push(identifiers.iter().map(|identifier| {
+            // In einer Klausel wird die Variable negiert, die in der Zeile wahr ist, im Minterm umgekehrt
+            if idToBool[identifier] == conjunctive {
+                format!("!{}", identifier)
+            } else {
+                identifier.clone()
+            }
+        }).collect());
+    }
 
-        let result = ev.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&a.root).unwrap());
+    if terms.is_empty() {
+        // Leere Konjunktion ist wahr, leere Disjunktion ist falsch
+        return format!("{}: [{}]", name, boolToString(conjunctive));
+    }
 
-        return format!("Ergebnis: {}", result);
+    let single = terms.len() == 1;
+    let formatted: Vec<String> = terms.iter().map(|literals| {
+        if literals.is_empty() {
+            boolToString(!conjunctive)
+        } else if literals.len() > 1 && !single {
+            format!("({})", profile::joinOperands(literals, literalOp))
         } else {
-            return "Es ist etwas mit der Syntax des Ausdruckes falsch!".to_string();
+            profile::joinOperands(literals, literalOp)
         }
+    }).collect();
+    format!("{}: [{}]", name, profile::joinOperands(&formatted, termOp))
 }
 
-fn commandTable(line: &Vec<Token>) -> String {
-    return "".to_string();
+fn commandLatex(line: &Vec<Token>) -> String {
+    match getFormula(line, &[]) {
+        Ok(formula) => format!("${}$", formula.root.toLatex()),
+        Err(message) => message
+    }
+}
+
+fn commandTree(line: &Vec<Token>) -> String {
+    match getFormula(line, &[]) {
+        Ok(formula) => formula.root.drawTree(),
+        Err(message) => message
+    }
+}
+
+fn commandProfile(line: &Vec<Token>) -> String {
+    if line[1].tType == TokenType::EOL {
+        return describeProfile(&profile::getActive());
+    }
+    if line[1].tType != TokenType::IDENTIFIER {
+        return format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). Erwartet wurde default, strict oder custom", line[1].start, line[1].content);
+    }
+
+    let selected = match line[1].content.as_str() {
+        "default" | "strict" if line[2].tType != TokenType::EOL => {
+            return format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). Nach dem Profil {} wurde nichts weiteres erwartet", line[2].start, line[2].content, line[1].content);
+        }
+        "default" => profile::Profile::standard(),
+        "strict" => profile::Profile::strict(),
+        "custom" if line[2].tType == TokenType::EOL => match profile::getCustom() {
+            Some(custom) => custom,
+            None => { return "Es wurde noch kein Profil custom festgelegt, z.B. profile custom [!&& !||] [^] [&&] [||] [-> <-] right [<->]".to_string(); }
+        },
+        "custom" => {
+            let levels = match getLevels(line) {
+                Ok(levels) => levels,
+                Err(message) => { return message; }
+            };
+            match profile::Profile::custom(&levels) {
+                Ok(custom) => custom,
+                Err(message) => { return message; }
+            }
+        }
+        _ => { return format!("Das Profil {} gibt es nicht. Erwartet wurde default, strict oder custom", line[1].content); }
+    };
+
+    profile::select(selected.clone());
+    format!("Das Profil {} ist jetzt aktiv\r\n{}", selected.name, describeProfile(&selected))
+}
+
+fn commandNotation(line: &Vec<Token>) -> String {
+    if line[1].tType == TokenType::EOL {
+        return describeNotation(getNotation());
+    }
+
+    let notation = match Notation::fromName(&line[1].content) {
+        Some(notation) if line[1].tType == TokenType::IDENTIFIER => notation,
+        _ => { return format!("Die Schreibweise {} gibt es nicht. Erwartet wurde default oder engineering", line[1].content); }
+    };
+    if line[2].tType != TokenType::EOL {
+        return format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). Nach der Schreibweise wurde nichts weiteres erwartet", line[2].start, line[2].content);
+    }
+
+    setNotation(notation);
+    format!("Die Schreibweise {} ist jetzt aktiv\r\n{}", notation.name(), describeNotation(notation))
+}
+
+fn describeNotation(notation: Notation) -> String {
+    match notation {
+        Notation::DEFAULT => "Aktive Schreibweise: default (Variablen sind Wörter, Operatoren wie && || ! ->)".to_string(),
+        Notation::ENGINEERING => "Aktive Schreibweise: engineering (Variablen sind Buchstaben mit optionalen Ziffern, + ist ||, ·, * oder Nebeneinanderschreiben ist &&, ein nachgestelltes ' oder ein vorangestelltes ~ ist !)".to_string()
+    }
+}
+
+// Ein default oder engineering als letztes Wort, außer bei profile und notation selbst
+fn findNotationOption(line: &Vec<Token>) -> Option<(usize, Notation)> {
+    if line.len() < 3 || (line[0].tType == TokenType::COMMAND && (line[0].content == "profile" || line[0].content == "notation")) {
+        return None;
+    }
+    let index = line.len() - 2;
+    if line[index].tType != TokenType::IDENTIFIER {
+        return None;
+    }
+    Notation::fromName(&line[index].content).map(|notation| (index, notation))
+}
+
+// Liest die Stufen von profile custom: je ein <AUSDRUCK> aus zweistelligen Operatoren, optional gefolgt von left oder right
+// Eine Stufe ist ein Ausdruck aus Operatoren wie [&& ^]. left oder right hinter einem Operator gilt nur für
+// diesen, hinter der Stufe für alle Operatoren der Stufe ohne eigene Angabe
+fn getLevels(line: &Vec<Token>) -> Result<Vec<Vec<(Operator, Associativity)>>, String> {
+    let mut levels: Vec<Vec<(Operator, Option<Associativity>)>> = vec![];
+    let mut i: usize = 2;
+    while line[i].tType != TokenType::EOL {
+        if let (Some(associativity), Some(level)) = (getAssociativity(&line[i]), levels.last_mut()) {
+            for (_, own) in level.iter_mut().filter(|(_, own)| own.is_none()) {
+                *own = Some(associativity);
+            }
+            i += 1;
+            continue;
+        }
+        if line[i].tType != TokenType::EXPRESSION {
+            return Err(format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). Erwartet wurde eine Stufe wie [&& ^], optional gefolgt von left oder right", line[i].start, line[i].content));
+        }
+
+        let mut operators: Vec<(Operator, Option<Associativity>)> = vec![];
+        for token in line[i].expression.iter().filter(|token| token.tType != TokenType::EOL) {
+            if let (Some(associativity), Some(last)) = (getAssociativity(token), operators.last_mut()) {
+                if last.1.is_none() {
+                    last.1 = Some(associativity);
+                    continue;
+                }
+            }
+            match Operator::fromTokenType(&token.tType) {
+                Some(operator) => operators.push((operator, None)),
+                None => { return Err(format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). In einer Stufe sind nur zweistellige Operatoren, jeweils optional gefolgt von left oder right, erlaubt", token.start, token.content)); }
+            }
+        }
+        if operators.is_empty() {
+            return Err(format!("Die Stufe an der Stelle {} ist leer", line[i].start));
+        }
+        levels.push(operators);
+        i += 1;
+    }
+    Ok(levels.into_iter().map(|level| level.into_iter().map(|(operator, own)| (operator, own.unwrap_or(Associativity::LEFT))).collect()).collect())
+}
+
+fn getAssociativity(token: &Token) -> Option<Associativity> {
+    if token.tType != TokenType::IDENTIFIER {
+        return None;
+    }
+    match token.content.as_str() {
+        "left" => Some(Associativity::LEFT),
+        "right" => Some(Associativity::RIGHT),
+        _ => None
+    }
+}
+
+fn describeProfile(active: &profile::Profile) -> String {
+    let header: Vec<String> = vec!["Stufe".to_string(), "Operatoren".to_string(), "Assoziativität".to_string()];
+    let mut rows: Vec<Vec<String>> = vec![vec!["1".to_string(), "!".to_string(), "-".to_string()]];
+    let mut precedences: Vec<u8> = vec![];
+    for (precedence, operators, associativity) in active.levels() {
+        // Die Stufen werden fortlaufend nummeriert, Einträge derselben Stufe tragen dieselbe Nummer
+        if precedences.last() != Some(&precedence) {
+            precedences.push(precedence);
+        }
+        let symbols: Vec<&str> = operators.iter().map(|operator| operator.symbol()).collect();
+        let direction = if associativity == Associativity::RIGHT { "rechts" } else { "links" };
+        rows.push(vec![(precedences.len() + 1).to_string(), symbols.join(" "), direction.to_string()]);
+    }
+
+    let note = if active.strict { " (jede Verknüpfung muss geklammert werden)" } else { "" };
+    format!("Aktives Profil: {}{}\r\n{}", active.name, note, renderTable(&header, &rows))
+}
+
+fn getFormula(line: &Vec<Token>, allowed: &[&str]) -> Result<Formula, String> {
+    if line.len() < 2 || line[1].tType != TokenType::EXPRESSION {
+        return Err(format!("Der Befehl {} erwartet einen <AUSDRUCK>, z.B. {} [a && b]", line[0].content, line[0].content));
+    }
+
+    let mut options: Vec<String> = vec![];
+    let mut i: usize = 2;
+    while line[i].tType != TokenType::EOL {
+        if line[i].tType == TokenType::IDENTIFIER && allowed.contains(&line[i].content.as_str()) {
+            options.push(line[i].content.clone());
+            i += 1;
+            continue;
+        }
+        if allowed.is_empty() {
+            return Err(format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). Nach dem <AUSDRUCK> wurde nichts weiteres erwartet", line[i].start, line[i].content));
+        }
+        return Err(format!("Es ist ein Fehler an der Stelle {} aufgetreten (Inhalt: {}). Nach dem <AUSDRUCK> sind nur die Optionen {} erlaubt", line[i].start, line[i].content, allowed.join(", ")));
+    }
+
+    let root = buildTree(&line[1].expression)?;
+    Ok(Formula {
+        text: line[1].content.clone(),
+        identifiers: getIdentifiers(&line[1].expression),
+        root,
+        options
+    })
+}
+
+// Tokenisiert die Zeile so, wie commandFinder sie liest, und gibt die Syntaxfehler mit zurück.
+// Wird in der REPL bei jedem Tastendruck für die Hervorhebung aufgerufen
+pub fn analyzeLine(line: &str) -> (Tokenizer, Vec<SyntaxError>) {
+    let mut tokenizer = Tokenizer::new(line.to_string());
+    tokenizer.tokenize();
+    if let Some((_, notation)) = findNotationOption(&tokenizer.tokens) {
+        tokenizer = Tokenizer::withNotation(line.to_string(), notation);
+        tokenizer.tokenize();
+    }
+    let errors = findSyntaxErrors(&tokenizer);
+    (tokenizer, errors)
+}
+
+// Sammelt die Fehler des Tokenizers und aller <AUSDRUCK>e, bevor ein Befehl ausgeführt wird
+fn findSyntaxErrors(tokenizer: &Tokenizer) -> Vec<SyntaxError> {
+    let mut errors = tokenizer.errors.clone();
+    // Die Stufen von profile custom bestehen nur aus Operatoren und werden von getLevels geprüft
+    let isProfile = tokenizer.tokens.first().is_some_and(|token| token.tType == TokenType::COMMAND && token.content == "profile");
+    for token in &tokenizer.tokens {
+        match token.tType {
+            TokenType::EXPRESSION if !isProfile => {
+                if let Err(expressionErrors) = Parser::new(&token.expression).parse() {
+                    errors.extend(expressionErrors);
+                }
+            }
+            TokenType::ERROR => errors.push(SyntaxError::unknown(&token.content, token.start, token.end)),
+            _ => {}
+        }
+    }
+    errors.sort_by_key(|error| error.start);
+    errors
+}
+
+fn renderSyntaxErrors(line: &str, errors: &Vec<SyntaxError>) -> String {
+    let rendered: Vec<String> = errors.iter().map(|error| error.render(line)).collect();
+    if errors.len() == 1 {
+        return rendered[0].clone();
+    }
+    format!("Es wurden {} Syntaxfehler gefunden:\r\n{}", errors.len(), rendered.join("\r\n"))
+}
+
+fn buildTree(expression: &Vec<Token>) -> Result<ASTNode, String> {
+    let mut ev = Evaluator {
+        expression: expression.clone(),
+        tree: AST { root: None }
+    };
+
+    if let Err(error) = ev.buildAST() {
+        return Err(format!("Es ist etwas mit der Syntax des Ausdruckes falsch! {}", error));
+    }
+    Ok(*ev.getAST().root.unwrap())
+}
+
+fn boolToString(value: bool) -> String {
+    u8::from(value).to_string()
+}
+
+fn getRow(identifiers: &Vec<String>, row: usize) -> HashMap<String, bool> {
+    let mut idToBool: HashMap<String, bool> = HashMap::new();
+    for (i, identifier) in identifiers.iter().enumerate() {
+        idToBool.insert(identifier.clone(), (row >> (identifiers.len() - 1 - i)) & 1 == 1);
+    }
+    idToBool
+}
+
+fn formatAssignment(identifiers: &Vec<String>, idToBool: &HashMap<String, bool>) -> String {
+    if identifiers.is_empty() {
+        return "(keine Variablen)".to_string();
+    }
+    let assignments: Vec<String> = identifiers.iter().map(|identifier| {
+        format!("{} = {}", identifier, boolToString(idToBool.get(identifier).copied().unwrap_or(false)))
+    }).collect();
+    assignments.join(", ")
+}
+
+// Bis maxTableVariables wird die Wahrheitswertetabelle durchlaufen, darüber per Fallunterscheidung
+// gesucht. Wird die Fallunterscheidung zu aufwendig, kommt die Meldung des Solvers zurück
+fn findAssignment(root: &ASTNode, identifiers: &Vec<String>, wanted: bool) -> Result<Option<HashMap<String, bool>>, String> {
+    if identifiers.len() <= maxTableVariables {
+        for row in 0..(1usize << identifiers.len()) {
+            let idToBool = getRow(identifiers, row);
+            if root.evaluate(&idToBool).unwrap() == wanted {
+                return Ok(Some(idToBool));
+            }
+        }
+        return Ok(None);
+    }
+
+    solver::findAssignment(root, wanted)
+}
+
+fn renderTable(header: &Vec<String>, rows: &Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count().max(1)).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut table = String::new();
+
+    let cells: Vec<String> = header.iter().enumerate().map(|(i, h)| format!(" {:^w$} ", h, w = widths[i])).collect();
+    table.push_str(&cells.join("|"));
+    table.push_str("\r\n");
+
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(w + 2)).collect();
+    table.push_str(&separator.join("+"));
+
+    for row in rows {
+        let cells: Vec<String> = row.iter().enumerate().map(|(i, c)| format!(" {:^w$} ", c, w = widths[i])).collect();
+        table.push_str("\r\n");
+        table.push_str(&cells.join("|"));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(line: &str) -> String {
+        let mut tokenizer = Tokenizer::withNotation(line.to_string(), Notation::DEFAULT);
+        tokenizer.tokenize();
+        commandFinder(&tokenizer)
+    }
+
+    #[test]
+    fn customProfileLevelsAreNoFormulas() {
+        let _guard = profile::testGuard();
+        let line = "profile custom [!&& !||] [^] [&&] [||] [-> <-] right [<->]";
+        assert!(analyzeLine(line).1.is_empty());
+        assert!(run(line).starts_with("Das Profil custom ist jetzt aktiv"));
+        assert!(!profile::isStrict());
+        assert_eq!(profile::associativity(&TokenType::CONVERSE), Associativity::RIGHT);
+        assert!(run("profile custom [&& a]").starts_with("Es ist ein Fehler an der Stelle 19 aufgetreten (Inhalt: a). In einer Stufe sind nur zweistellige Operatoren"));
+    }
+
+    #[test]
+    fn associativityPerOperator() {
+        let _guard = profile::testGuard();
+        let standard = run("profile");
+        assert!(standard.contains("|     ->     |     rechts"));
+        assert!(standard.contains("|     <-     |     links"));
+
+        // Das Profil default lässt sich mit custom nachbauen
+        run("profile custom [^ !&& !||] [&&] [||] [-> right <-] [<->]");
+        let (custom, standard) = (profile::getActive(), profile::Profile::standard());
+        assert_eq!(custom.precedences, standard.precedences);
+        assert_eq!(custom.associativities, standard.associativities);
+
+        run("profile custom [&& || right ^ !&& !||] left [-> <- <->] right");
+        assert_eq!(profile::associativity(&TokenType::OR), Associativity::RIGHT);
+        assert_eq!(profile::associativity(&TokenType::XOR), Associativity::LEFT);
+        assert_eq!(profile::associativity(&TokenType::CONVERSE), Associativity::RIGHT);
+    }
+
+    #[test]
+    fn syntaxErrorsStopTheCommand() {
+        let _guard = profile::testGuard();
+        assert_eq!(run("[a && b] a 1 b 1"), "Ergebnis: 1");
+        assert!(run("table [a => b]").starts_with("Syntaxfehler an der Stelle 9"));
+        assert!(run("valid [a && && b] [c ||]").starts_with("Es wurden 2 Syntaxfehler gefunden"));
+    }
+
+    #[test]
+    fn positionsCountCharacters() {
+        let _guard = profile::testGuard();
+        assert!(run("valid [a] b").starts_with("Es ist ein Fehler an der Stelle 10 aufgetreten (Inhalt: b)"));
+        assert!(run("[a && b] a 1 b x").starts_with("Es ist ein Fehler an der Stelle 15 aufgetreten (Inhalt: x)"));
+        assert_eq!(run("[a&&!b] a 1 b 0"), "Ergebnis: 1");
+        assert_eq!(run("[!!a] a 1"), "Ergebnis: 1");
+    }
+
+    #[test]
+    fn positionsBeyondSixteenBits() {
+        let _guard = profile::testGuard();
+        let padding = " ".repeat(70000);
+        let (_, errors) = analyzeLine(&format!("valid [{}a => b]", padding));
+        assert_eq!((errors[0].start, errors[0].end), (70009, 70010));
+    }
+
+    #[test]
+    fn tooManyVariablesForTheTable() {
+        let _guard = profile::testGuard();
+        let variables: Vec<String> = (0..70).map(|i| format!("x{}", i)).collect();
+        let result = run(&format!("table [{}]", variables.join(" && ")));
+        assert!(result.contains("70 Variablen, die Tabelle wäre mit 2^70 Zeilen zu groß"));
+    }
 }
@@ -0,0 +1,122 @@
+use crate::editor::isWordChar;
+use crate::evaluator::tokenizer::{self, Token, TokenType, Tokenizer};
+
+// Befehle, die nur die REPL kennt
+static replCommands: [&str; 2] = ["clear", "help"];
+
+// Merkt sich die Variablen der bisherigen Eingaben, damit Tab sie ergänzen kann
+pub struct Completer {
+    identifiers: Vec<String>
+}
+
+fn collectIdentifiers(tokens: &Vec<Token>, identifiers: &mut Vec<String>) {
+    for token in tokens {
+        if token.tType == TokenType::IDENTIFIER && !identifiers.contains(&token.content) {
+            identifiers.push(token.content.clone());
+        }
+        collectIdentifiers(&token.expression, identifiers);
+    }
+}
+
+fn isOperatorChar(c: char) -> bool {
+    "&|!-<>^".contains(c)
+}
+
+// Ob vor dem Cursor noch eine [ offen ist, dort werden auch Operatoren und Schlüsselwörter ergänzt
+fn insideExpression(before: &str) -> bool {
+    before.chars().filter(|c| *c == '[').count() > before.chars().filter(|c| *c == ']').count()
+}
+
+// Das Wort bzw. der angefangene Operator vor dem Cursor mit seiner Startposition, das Tab ergänzt
+pub fn findPrefix(line: &str, cursor: usize) -> (usize, String) {
+    let chars: Vec<char> = line.chars().collect();
+    let mut start = cursor;
+    while start > 0 && isWordChar(chars[start - 1]) {
+        start -= 1;
+    }
+    if start == cursor {
+        while start > 0 && isOperatorChar(chars[start - 1]) {
+            start -= 1;
+        }
+    }
+    (start, chars[start..cursor].iter().collect())
+}
+
+// Längster gemeinsamer Anfang aller Kandidaten
+pub fn commonPrefix(candidates: &Vec<String>) -> String {
+    let mut prefix: Vec<char> = candidates[0].chars().collect();
+    for candidate in &candidates[1..] {
+        let length = prefix.iter().zip(candidate.chars()).take_while(|(a, b)| **a == *b).count();
+        prefix.truncate(length);
+    }
+    prefix.into_iter().collect()
+}
+
+impl Completer {
+    pub fn new() -> Completer {
+        Completer { identifiers: vec![] }
+    }
+
+    pub fn learn(&mut self, tokens: &Vec<Token>) {
+        collectIdentifiers(tokens, &mut self.identifiers);
+    }
+
+    // Kandidaten für das Wort prefix, das bei start beginnt. Am Zeilenanfang sind das die Befehle,
+    // sonst die Variablen der Zeile (ohne das Wort selbst) und der bisherigen Eingaben. Innerhalb
+    // von [ ] kommen die Operatoren und die Schlüsselwörter wie and oder wenn dazu
+    pub fn candidates(&self, line: &str, start: usize, prefix: &str) -> Vec<String> {
+        let before: String = line.chars().take(start).collect();
+        let mut candidates: Vec<String> = vec![];
+        let inside = insideExpression(&before);
+
+        if prefix.starts_with(isOperatorChar) {
+            if inside {
+                candidates.extend(tokenizer::operators.iter().filter(|operator| operator.starts_with(prefix)).map(|operator| operator.to_string()));
+            }
+        } else if before.trim().is_empty() {
+            for command in tokenizer::commands.iter().chain(replCommands.iter()) {
+                if command.starts_with(prefix) {
+                    candidates.push(command.to_string());
+                }
+            }
+        } else {
+            let after: String = line.chars().skip(start + prefix.chars().count()).collect();
+            let mut current = Tokenizer::new(format!("{}{}", before, after));
+            current.tokenize();
+            let mut identifiers: Vec<String> = vec![];
+            collectIdentifiers(&current.tokens, &mut identifiers);
+            for identifier in identifiers.iter().chain(self.identifiers.iter()) {
+                if identifier.starts_with(prefix) && !candidates.contains(identifier) {
+                    candidates.push(identifier.clone());
+                }
+            }
+            if inside && !prefix.is_empty() {
+                candidates.extend(tokenizer::keywordMap.keys().filter(|keyword| keyword.starts_with(prefix)).map(|keyword| keyword.to_string()));
+            }
+        }
+        candidates.sort();
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complete(line: &str) -> Vec<String> {
+        let (start, prefix) = findPrefix(line, line.chars().count());
+        Completer::new().candidates(line, start, &prefix)
+    }
+
+    #[test]
+    fn candidatesDependOnPosition() {
+        assert_eq!(complete("ta"), vec!["table"]);
+        assert_eq!(complete("s"), vec!["satis", "semcons"]);
+        assert_eq!(complete("[alpha && beta] al"), vec!["alpha"]);
+        assert_eq!(complete("[a <"), vec!["<-", "<->"]);
+        assert_eq!(complete("[a !"), vec!["!", "!&&", "!||"]);
+        assert_eq!(complete("[a an"), vec!["and"]);
+        assert_eq!(complete("[wert && w"), vec!["wenn", "wert"]);
+        assert!(complete("[a] a -").is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+use std::io::{self, Write};
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    queue, style::{Attribute, SetAttribute, SetForegroundColor},
+    terminal::{self, Clear, ClearType},
+};
+use crate::highlight::{self, CharStyle};
+
+pub const prompt: &str = "λ> ";
+pub const continuationPrompt: &str = ".. ";
+
+pub struct LineEditor {
+    buffer: Vec<char>,
+    cursor: usize,
+    // Erstes sichtbares Zeichen, wenn die Zeile breiter als das Terminal ist
+    offset: usize,
+    prompt: String,
+    // Die bisherigen Zeilen einer mehrzeiligen Eingabe, damit die Hervorhebung den Zusammenhang kennt
+    context: String
+}
+
+pub fn isWordChar(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Eine Eingabe, in der am Ende noch eine [ oder ( offen ist, wird in der nächsten Zeile fortgesetzt.
+// Passt eine schließende Klammer nicht zur offenen, ist das ein Syntaxfehler und keine Fortsetzung
+pub fn isIncomplete(line: &str) -> bool {
+    let mut open: Vec<char> = vec![];
+    for c in line.chars() {
+        match c {
+            '[' | '(' => open.push(c),
+            ']' | ')' => {
+                let expected = if c == ']' { '[' } else { '(' };
+                if open.pop() != Some(expected) {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    !open.is_empty()
+}
+
+fn applyStyle(stdout: &mut impl Write, style: CharStyle) -> io::Result<()> {
+    queue!(stdout, SetAttribute(Attribute::Reset))?;
+    if let Some(colour) = style.colour {
+        queue!(stdout, SetForegroundColor(colour))?;
+    }
+    if style.underline {
+        queue!(stdout, SetAttribute(Attribute::Underlined))?;
+    }
+    if style.bracket {
+        queue!(stdout, SetAttribute(Attribute::Reverse))?;
+    }
+    Ok(())
+}
+
+impl LineEditor {
+    pub fn new() -> LineEditor {
+        LineEditor { buffer: vec![], cursor: 0, offset: 0, prompt: prompt.to_string(), context: String::new() }
+    }
+
+    pub fn text(&self) -> String {
+        self.buffer.iter().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.cursor = 0;
+        self.offset = 0;
+    }
+
+    // Ersetzt die Zeile, z.B. durch einen Eintrag der Historie. Der Cursor steht danach am Ende
+    pub fn set(&mut self, text: &str) {
+        self.buffer = text.chars().collect();
+        self.cursor = self.buffer.len();
+        self.offset = 0;
+    }
+
+    pub fn setPrompt(&mut self, text: &str) {
+        self.prompt = text.to_string();
+    }
+
+    pub fn setContext(&mut self, text: &str) {
+        self.context = text.to_string();
+    }
+
+    pub fn position(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn replaceWord(&mut self, start: usize, text: &str) {
+        let replacement: Vec<char> = text.chars().collect();
+        let length = replacement.len();
+        self.buffer.splice(start..self.cursor, replacement);
+        self.cursor = start + length;
+    }
+
+    fn insert(&mut self, c: char) {
+        self.buffer.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    // Anfang des Wortes vor dem Cursor, Trennzeichen davor werden übersprungen
+    fn previousWord(&self) -> usize {
+        let mut position = self.cursor;
+        while position > 0 && !isWordChar(self.buffer[position - 1]) {
+            position -= 1;
+        }
+        while position > 0 && isWordChar(self.buffer[position - 1]) {
+            position -= 1;
+        }
+        position
+    }
+
+    // Ende des Wortes nach dem Cursor
+    fn nextWord(&self) -> usize {
+        let mut position = self.cursor;
+        while position < self.buffer.len() && !isWordChar(self.buffer[position]) {
+            position += 1;
+        }
+        while position < self.buffer.len() && isWordChar(self.buffer[position]) {
+            position += 1;
+        }
+        position
+    }
+
+    // Gibt zurück, ob die Taste die Zeile oder den Cursor verändert hat
+    pub fn handleKey(&mut self, key: KeyEvent) -> bool {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        match key.code {
+            KeyCode::Char('a') if control => self.cursor = 0,
+            KeyCode::Char('e') if control => self.cursor = self.buffer.len(),
+            KeyCode::Char('b') if control => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Char('f') if control => self.cursor = (self.cursor + 1).min(self.buffer.len()),
+            KeyCode::Char('w') if control => {
+                let start = self.previousWord();
+                self.buffer.drain(start..self.cursor);
+                self.cursor = start;
+            }
+            KeyCode::Char('u') if control => {
+                self.buffer.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            KeyCode::Char('k') if control => {
+                self.buffer.truncate(self.cursor);
+            }
+            KeyCode::Char('b') if alt => self.cursor = self.previousWord(),
+            KeyCode::Char('f') if alt => self.cursor = self.nextWord(),
+            KeyCode::Char('d') if alt => {
+                let end = self.nextWord();
+                self.buffer.drain(self.cursor..end);
+            }
+            KeyCode::Char(_) if control || alt => { return false; }
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Left if control => self.cursor = self.previousWord(),
+            KeyCode::Right if control => self.cursor = self.nextWord(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.buffer.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.buffer.len(),
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.buffer.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.buffer.len() => {
+                self.buffer.remove(self.cursor);
+            }
+            _ => { return false; }
+        }
+        true
+    }
+
+    // Zeichnet die Zeile mit Prompt neu. Ist sie breiter als das Terminal, wird nur der Ausschnitt
+    // um den Cursor gezeigt
+    pub fn render(&mut self, stdout: &mut impl Write) -> io::Result<()> {
+        let width = terminal::size().ok().map(|(columns, _)| columns as usize).filter(|&columns| columns > 0).unwrap_or(80);
+        let promptWidth = self.prompt.chars().count();
+        let available = width.saturating_sub(promptWidth + 1).max(1);
+
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor > self.offset + available {
+            self.offset = self.cursor - available;
+        }
+        // Ist die Zeile wieder kürzer geworden, wird so wenig wie möglich abgeschnitten
+        self.offset = self.offset.min(self.buffer.len().saturating_sub(available));
+
+        let end = (self.offset + available).min(self.buffer.len());
+        let skipped = self.context.chars().count();
+        let styles = highlight::highlight(&format!("{}{}", self.context, self.text()), skipped + self.cursor);
+        let styles = &styles[skipped..];
+        queue!(stdout, cursor::MoveToColumn(0), Clear(ClearType::CurrentLine))?;
+        write!(stdout, "{}", self.prompt)?;
+
+        let mut current = CharStyle::default();
+        for (c, style) in self.buffer[self.offset..end].iter().zip(&styles[self.offset..end]) {
+            if *style != current {
+                current = *style;
+                applyStyle(stdout, current)?;
+            }
+            write!(stdout, "{}", c)?;
+        }
+        // Ein Fehler hinter dem letzten Zeichen wird an einem Leerzeichen unterstrichen
+        if end == self.buffer.len() && styles[end].underline {
+            applyStyle(stdout, styles[end])?;
+            write!(stdout, " ")?;
+        }
+        queue!(stdout, SetAttribute(Attribute::Reset))?;
+        queue!(stdout, cursor::MoveToColumn((promptWidth + self.cursor - self.offset) as u16))?;
+        stdout.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn onlyUnmatchedOpenersContinue() {
+        assert!(isIncomplete("table [a && (b"));
+        assert!(isIncomplete("valid [a] [b ||"));
+        assert!(!isIncomplete("[a && (b)] a 1 b 0"));
+        assert!(!isIncomplete("[a && (b]"));
+        assert!(!isIncomplete("[a)] (["));
+    }
+}
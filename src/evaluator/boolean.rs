@@ -1,44 +1,35 @@
-pub fn NOT(bool1: String) -> String {
-    if bool1 == "1".to_string() {
-        return "0".to_string()
-    }
-    "1".to_string()
+pub fn NOT(bool1: bool) -> bool {
+    !bool1
 }
 
-pub fn AND(bool1: String, bool2: String) -> String {
-    if bool1 == "1".to_string() && (bool1 == bool2) {
-        return "1".to_string()
-    }
-    "0".to_string()
+pub fn AND(bool1: bool, bool2: bool) -> bool {
+    bool1 && bool2
 }
 
-pub fn OR(bool1: String, bool2: String) -> String {
-    if bool1 == "1".to_string() || bool2 == "1".to_string() {
-        return "1".to_string()
-    }
-    "0".to_string()
+pub fn OR(bool1: bool, bool2: bool) -> bool {
+    bool1 || bool2
 }
 
-pub fn IMPLIES(bool1: String, bool2: String) -> String {
-    return OR(NOT(bool1.clone()), bool2.clone())
+pub fn IMPLIES(bool1: bool, bool2: bool) -> bool {
+    return OR(NOT(bool1), bool2)
 }
 
-pub fn CONVERSE(bool1: String, bool2: String) -> String {
-    return IMPLIES(bool2.clone(), bool1.clone())
+pub fn CONVERSE(bool1: bool, bool2: bool) -> bool {
+    return IMPLIES(bool2, bool1)
 }
 
-pub fn EQUIVALENCE(bool1: String, bool2: String) -> String {
-    return AND(IMPLIES(bool1.clone(), bool2.clone()), IMPLIES(bool2.clone(), bool1.clone()))
+pub fn EQUIVALENCE(bool1: bool, bool2: bool) -> bool {
+    return AND(IMPLIES(bool1, bool2), IMPLIES(bool2, bool1))
 }
 
-pub fn XOR(bool1: String, bool2: String) -> String {
-    return NOT(EQUIVALENCE(bool1.clone(), bool2.clone()))
+pub fn XOR(bool1: bool, bool2: bool) -> bool {
+    return NOT(EQUIVALENCE(bool1, bool2))
 }
 
-pub fn NAND(bool1: String, bool2: String) -> String {
-    return NOT(AND(bool1.clone(), bool2.clone()))
+pub fn NAND(bool1: bool, bool2: bool) -> bool {
+    return NOT(AND(bool1, bool2))
 }
 
-pub fn NOR(bool1: String, bool2: String) -> String {
-    return NOT(OR(bool1.clone(), bool2.clone()))
+pub fn NOR(bool1: bool, bool2: bool) -> bool {
+    return NOT(OR(bool1, bool2))
 }
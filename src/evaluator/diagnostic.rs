@@ -0,0 +1,107 @@
+use std::fmt;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum SyntaxErrorKind {
+    UNEXPECTEDTOKEN(String),
+    MISSINGOPERAND,
+    UNCLOSEDPAREN,
+    UNOPENEDPAREN,
+    MISSINGTHEN,
+    UNKNOWNOPERATOR(String),
+    UNKNOWNCHARACTER(String),
+    MISSINGBRACKET,
+    AMBIGUOUS(String),
+}
+
+// start und end sind Zeichenpositionen in der eingegebenen Zeile, end zeigt auf das letzte Zeichen
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SyntaxError {
+    pub kind: SyntaxErrorKind,
+    pub start: usize,
+    pub end: usize,
+    pub expected: String
+}
+
+// Häufige Verwechslungen, z.B. aus anderen Programmiersprachen
+fn suggestOperator(operator: &str) -> Option<&'static str> {
+    match operator {
+        "&" => Some("&&"),
+        "|" => Some("||"),
+        "=>" | ">" | "-->" => Some("->"),
+        "<=" | "<--" => Some("<-"),
+        "<=>" | "=" | "==" | "<-->" => Some("<->"),
+        "!=" => Some("^"),
+        "!&" => Some("!&&"),
+        "!|" => Some("!||"),
+        _ => None
+    }
+}
+
+impl SyntaxError {
+    pub fn new(kind: SyntaxErrorKind, start: usize, end: usize, expected: &str) -> SyntaxError {
+        SyntaxError { kind, start, end, expected: expected.to_string() }
+    }
+
+    pub fn unknown(content: &str, start: usize, end: usize) -> SyntaxError {
+        if !content.starts_with(|c: char| "&|!-^<=>".contains(c)) {
+            return SyntaxError::new(SyntaxErrorKind::UNKNOWNCHARACTER(content.to_string()), start, end, "eine Variable, 0, 1, ein Operator oder eine Klammer");
+        }
+        let expected = match suggestOperator(content) {
+            Some(operator) => format!("ein Operator wie && || ! -> <- <-> ^ !&& !|| (vermutlich {})", operator),
+            None => "ein Operator wie && || ! -> <- <-> ^ !&& !||".to_string()
+        };
+        SyntaxError { kind: SyntaxErrorKind::UNKNOWNOPERATOR(content.to_string()), start, end, expected }
+    }
+
+    // Gibt die Zeile mit dem unterstrichenen Fehler wieder:
+    //   table [a => b]
+    //            ^~
+    pub fn render(&self, line: &str) -> String {
+        let underline = format!("{}^{}", " ".repeat(self.start), "~".repeat(self.end.saturating_sub(self.start)));
+        format!("Syntaxfehler an der Stelle {}: {}\r\n    {}\r\n    {}\r\nErwartet wurde {}", self.start, self, line, underline, self.expected)
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            SyntaxErrorKind::UNEXPECTEDTOKEN(content) => write!(f, "Das Zeichen {} wurde an dieser Stelle nicht erwartet", content),
+            SyntaxErrorKind::MISSINGOPERAND => write!(f, "Hier fehlt ein Operand"),
+            SyntaxErrorKind::UNCLOSEDPAREN => write!(f, "Die Klammer ( wird nicht geschlossen"),
+            SyntaxErrorKind::UNOPENEDPAREN => write!(f, "Zu der Klammer ) gibt es keine öffnende Klammer"),
+            SyntaxErrorKind::MISSINGTHEN => write!(f, "Zu dem wenn fehlt das dann"),
+            SyntaxErrorKind::UNKNOWNOPERATOR(content) => write!(f, "Den Operator {} gibt es nicht", content),
+            SyntaxErrorKind::UNKNOWNCHARACTER(content) => write!(f, "Das Zeichen {} ist unbekannt", content),
+            SyntaxErrorKind::MISSINGBRACKET => write!(f, "Die eckige Klammer [ wird nicht geschlossen"),
+            SyntaxErrorKind::AMBIGUOUS(content) => write!(f, "Im Profil strict muss jede Verknüpfung geklammert werden, vor dem Operator {} fehlt eine Klammer", content)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknownOperatorsGetASuggestion() {
+        let error = SyntaxError::unknown("=>", 9, 10);
+        assert_eq!(error.kind, SyntaxErrorKind::UNKNOWNOPERATOR("=>".to_string()));
+        assert!(error.expected.ends_with("(vermutlich ->)"));
+        assert!(!SyntaxError::unknown("<=<", 0, 2).expected.contains("vermutlich"));
+        assert_eq!(SyntaxError::unknown("$", 4, 4).kind, SyntaxErrorKind::UNKNOWNCHARACTER("$".to_string()));
+    }
+
+    #[test]
+    fn renderUnderlinesTheSpan() {
+        let error = SyntaxError::unknown("=>", 9, 10);
+        let lines: Vec<String> = error.render("table [a => b]").split("\r\n").map(|line| line.to_string()).collect();
+        assert_eq!(lines, vec![
+            "Syntaxfehler an der Stelle 9: Den Operator => gibt es nicht",
+            "    table [a => b]",
+            "             ^~",
+            "Erwartet wurde ein Operator wie && || ! -> <- <-> ^ !&& !|| (vermutlich ->)"
+        ]);
+        let missing = SyntaxError::new(SyntaxErrorKind::MISSINGOPERAND, 11, 11, "eine Variable");
+        assert!(missing.render("valid [a &&]").contains("\r\n               ^\r\n"));
+    }
+}
@@ -1,6 +1,15 @@
 mod boolean;
+pub mod diagnostic;
+pub mod normalform;
+pub mod parser;
+pub mod profile;
+pub mod solver;
 pub mod tokenizer;
 
+use std::collections::HashMap;
+use std::fmt;
+use tokenizer::{Associativity, TokenType};
+
 #[derive(Debug, Clone)]
 pub struct AST {
     pub root: Option<Box<ASTNode>>
@@ -12,12 +21,115 @@ impl AST {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct ASTNode {
-    pub nType: String,
-    pub content: String,
-    pub left: Option<Box<ASTNode>>,
-    pub right: Option<Box<ASTNode>>
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum Operator {
+    AND,
+    OR,
+    IMPLIES,
+    CONVERSE,
+    EQUIVALENCE,
+    XOR,
+    NAND,
+    NOR,
+}
+
+impl Operator {
+    pub fn fromTokenType(tType: &TokenType) -> Option<Operator> {
+        match tType {
+            TokenType::AND => Some(Operator::AND),
+            TokenType::OR => Some(Operator::OR),
+            TokenType::IMPLIES => Some(Operator::IMPLIES),
+            TokenType::CONVERSE => Some(Operator::CONVERSE),
+            TokenType::EQUIVALENCE => Some(Operator::EQUIVALENCE),
+            TokenType::XOR => Some(Operator::XOR),
+            TokenType::NAND => Some(Operator::NAND),
+            TokenType::NOR => Some(Operator::NOR),
+            _ => None
+        }
+    }
+
+    pub fn tokenType(&self) -> TokenType {
+        match self {
+            Operator::AND => TokenType::AND,
+            Operator::OR => TokenType::OR,
+            Operator::IMPLIES => TokenType::IMPLIES,
+            Operator::CONVERSE => TokenType::CONVERSE,
+            Operator::EQUIVALENCE => TokenType::EQUIVALENCE,
+            Operator::XOR => TokenType::XOR,
+            Operator::NAND => TokenType::NAND,
+            Operator::NOR => TokenType::NOR
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operator::AND => "&&",
+            Operator::OR => "||",
+            Operator::IMPLIES => "->",
+            Operator::CONVERSE => "<-",
+            Operator::EQUIVALENCE => "<->",
+            Operator::XOR => "^",
+            Operator::NAND => "!&&",
+            Operator::NOR => "!||"
+        }
+    }
+
+    pub fn latex(&self) -> &'static str {
+        match self {
+            Operator::AND => "\\land",
+            Operator::OR => "\\lor",
+            Operator::IMPLIES => "\\rightarrow",
+            Operator::CONVERSE => "\\leftarrow",
+            Operator::EQUIVALENCE => "\\leftrightarrow",
+            Operator::XOR => "\\oplus",
+            Operator::NAND => "\\uparrow",
+            Operator::NOR => "\\downarrow"
+        }
+    }
+
+    pub fn precedence(&self) -> u8 {
+        profile::precedence(&self.tokenType())
+    }
+
+    pub fn associativity(&self) -> Associativity {
+        profile::associativity(&self.tokenType())
+    }
+
+    pub fn apply(&self, bool1: bool, bool2: bool) -> bool {
+        match self {
+            Operator::AND => boolean::AND(bool1, bool2),
+            Operator::OR => boolean::OR(bool1, bool2),
+            Operator::IMPLIES => boolean::IMPLIES(bool1, bool2),
+            Operator::CONVERSE => boolean::CONVERSE(bool1, bool2),
+            Operator::EQUIVALENCE => boolean::EQUIVALENCE(bool1, bool2),
+            Operator::XOR => boolean::XOR(bool1, bool2),
+            Operator::NAND => boolean::NAND(bool1, bool2),
+            Operator::NOR => boolean::NOR(bool1, bool2)
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum ASTNode {
+    VARIABLE(String),
+    CONSTANT(bool),
+    NOT(Box<ASTNode>),
+    BINARY(Operator, Box<ASTNode>, Box<ASTNode>),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum EvaluationError {
+    SYNTAX(diagnostic::SyntaxError),
+    UNASSIGNED(String),
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvaluationError::SYNTAX(error) => write!(f, "{}", error),
+            EvaluationError::UNASSIGNED(identifier) => write!(f, "Der Variable {} wurde kein Wert zugewiesen", identifier)
+        }
+    }
 }
 
 pub struct Evaluator {
@@ -29,83 +141,183 @@ impl Evaluator {
     pub fn getAST(&self) -> AST {
         self.tree.clone()
     }
-    
-    pub fn buildAST(&mut self) {
-        self.tree = AST { root: Some(Box::new(self.parseExpr(self.expression.clone()))) };
+
+    pub fn buildAST(&mut self) -> Result<(), EvaluationError> {
+        let root = parser::Parser::new(&self.expression).parse().map_err(|errors| EvaluationError::SYNTAX(errors[0].clone()))?;
+        self.tree.setRoot(&root);
+        Ok(())
     }
+}
+
+// Nur x_12 und x_{12} werden zu x_{12}, weil sie dieselbe Variable sind. x12 und x[12] sind andere Variablen
+// und bleiben deshalb stehen, x_{max} bleibt x_{max}. Andere _ wie in req_ok gehören zum Namen
+fn latexIdentifier(identifier: &str) -> String {
+    let (name, index) = if let Some(split) = identifier.strip_suffix('}').and_then(|rest| rest.rsplit_once("_{")) {
+        split
+    } else if let Some(split) = identifier.rsplit_once('_').filter(|(_, index)| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit())) {
+        split
+    } else {
+        (identifier, "")
+    };
 
-    fn searchPeak(&self, expression: &Vec<tokenizer::Token>) -> u16 {
-        let mut maxVal: u8 = 0;
-        let mut pos: u16 = 0;
+    if name.is_empty() || index.is_empty() {
+        return identifier.replace('_', "\\_");
+    }
+    format!("{}_{{{}}}", name.replace('_', "\\_"), index)
+}
 
-        for (index, token) in expression.iter().enumerate() {
-            if let Some(&currPrec) = tokenizer::precedences.get(&token.tType) {
-                if currPrec > maxVal {
-                    maxVal = currPrec;
-                    pos = index as u16;
-                }
-            } else {
-                println!("Warning: Token type {:?} not found in precedences", token.tType);
+impl ASTNode {
+    // Alle zusammengesetzten Teilformeln, jede nach ihren eigenen Teilformeln
+    pub fn getSubformulas(&self, subformulas: &mut Vec<ASTNode>) {
+        match self {
+            ASTNode::NOT(operand) => {
+                operand.getSubformulas(subformulas);
+                subformulas.push(self.clone());
+            }
+            ASTNode::BINARY(_, left, right) => {
+                left.getSubformulas(subformulas);
+                right.getSubformulas(subformulas);
+                subformulas.push(self.clone());
             }
+            _ => {}
         }
-        pos
     }
 
-    fn parseExpr(&self, expression: Vec<tokenizer::Token>) -> ASTNode {
-        let mut root: ASTNode = ASTNode { nType: "".to_string(), content: "".to_string(), left: None, right: None };
-
-        if expression.is_empty() {
-            return root;
+    pub fn evaluate(&self, idToBool: &HashMap<String, bool>) -> Result<bool, EvaluationError> {
+        match self {
+            ASTNode::CONSTANT(value) => Ok(*value),
+            ASTNode::VARIABLE(identifier) => idToBool.get(identifier).copied().ok_or(EvaluationError::UNASSIGNED(identifier.clone())),
+            ASTNode::NOT(operand) => Ok(boolean::NOT(operand.evaluate(idToBool)?)),
+            ASTNode::BINARY(operator, left, right) => Ok(operator.apply(left.evaluate(idToBool)?, right.evaluate(idToBool)?))
         }
+    }
 
-        let index = self.searchPeak(&expression);
-        let peak = &expression[index as usize];
+    fn operand(&self, child: &ASTNode, isLeft: bool, latex: bool) -> String {
+        let needsParens = match (self, child) {
+            (ASTNode::NOT(_), ASTNode::BINARY(..)) => true,
+            (ASTNode::BINARY(..), ASTNode::BINARY(..)) if profile::isStrict() => true,
+            (ASTNode::BINARY(parent, _, _), ASTNode::BINARY(operator, _, _)) => {
+                // Bei gleicher Präzedenz entscheidet links die Assoziativität des Kindes und rechts die des Elternknotens
+                operator.precedence() > parent.precedence() || (operator.precedence() == parent.precedence() && if isLeft {
+                    operator.associativity() == Associativity::RIGHT
+                } else {
+                    parent.associativity() == Associativity::LEFT
+                })
+            }
+            _ => false
+        };
 
-        if peak.tType == tokenizer::TokenType::BOOL {
-            root.nType = "bool".to_string();
-            root.content = peak.content.clone();
+        if needsParens {
+            format!("({})", child.render(latex))
+        } else {
+            child.render(latex)
         }
+    }
 
-        if peak.tType == tokenizer::TokenType::BLOCK {
-            return self.parseExpr(peak.expression.clone());
+    fn render(&self, latex: bool) -> String {
+        match self {
+            ASTNode::NOT(operand) if latex => format!("\\neg {}", self.operand(operand, false, latex)),
+            ASTNode::NOT(operand) => format!("!{}", self.operand(operand, false, latex)),
+            ASTNode::BINARY(operator, left, right) => {
+                let symbol = if latex { operator.latex() } else { operator.symbol() };
+                format!("{} {} {}", self.operand(left, true, latex), symbol, self.operand(right, false, latex))
+            }
+            ASTNode::CONSTANT(value) if latex => (if *value { "\\top" } else { "\\bot" }).to_string(),
+            ASTNode::CONSTANT(value) => u8::from(*value).to_string(),
+            ASTNode::VARIABLE(identifier) if latex => latexIdentifier(identifier),
+            ASTNode::VARIABLE(identifier) => identifier.clone()
         }
+    }
 
-        if peak.tType == tokenizer::TokenType::NOT {
-            let rhs: Vec<tokenizer::Token> = expression[(index + 1) as usize..].to_vec();
-            root.nType = "unaryOp".to_string();
-            root.content = peak.content.clone();
-            root.right = Some(Box::new(self.parseExpr(rhs)));
-            return root;
-        }
+    pub fn toLatex(&self) -> String {
+        self.render(true)
+    }
 
-        if tokenizer::operatorTypes.contains(&peak.tType) && peak.tType != tokenizer::TokenType::NOT {
-            
-            root.nType = "binaryOp".to_string();
-            root.content = peak.content.clone();
+    fn label(&self) -> String {
+        match self {
+            ASTNode::NOT(_) => "!".to_string(),
+            ASTNode::BINARY(operator, _, _) => operator.symbol().to_string(),
+            _ => self.render(false)
+        }
+    }
 
-            let lhs: Vec<tokenizer::Token> = expression[..index as usize].to_vec();
-            let rhs: Vec<tokenizer::Token> = expression[((index+1) as usize)..].to_vec();
-            root.left = Some(Box::new(self.parseExpr(lhs)));
-            root.right = Some(Box::new(self.parseExpr(rhs)));
+    pub fn drawTree(&self) -> String {
+        let mut lines: Vec<String> = vec![self.label()];
+        self.drawChildren("", &mut lines);
+        lines.join("\r\n")
+    }
 
+    fn drawChildren(&self, prefix: &str, lines: &mut Vec<String>) {
+        let children: Vec<&ASTNode> = match self {
+            ASTNode::NOT(operand) => vec![operand],
+            ASTNode::BINARY(_, left, right) => vec![left, right],
+            _ => vec![]
+        };
+        for (i, child) in children.iter().enumerate() {
+            let isLast = i + 1 == children.len();
+            lines.push(format!("{}{}{}", prefix, if isLast { "└── " } else { "├── " }, child.label()));
+            child.drawChildren(&format!("{}{}", prefix, if isLast { "    " } else { "│   " }), lines);
         }
-        root
-    }
-
-    pub fn evaluate(&self, node: &ASTNode) -> String {
-        match node.content.as_str() {
-            _ if node.nType.as_str() == "bool" => { return node.content.clone() },
-            "&&" => { return boolean::AND(self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.left).unwrap()), self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.right).unwrap())) },
-            "||" => { return boolean::OR(self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.left).unwrap()), self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.right).unwrap())) },
-            "!" => { return boolean::NOT(self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.right).unwrap())) },
-            "->" => { return boolean::IMPLIES(self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.left).unwrap()), self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.right).unwrap())) },
-            "<-" => { return boolean::CONVERSE(self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.left).unwrap()), self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.right).unwrap())) },
-            "<->" => { return boolean::EQUIVALENCE(self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.left).unwrap()), self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.right).unwrap())) },
-            "^" => { return boolean::XOR(self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.left).unwrap()), self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.right).unwrap())) },
-            "!&&" => { return boolean::NAND(self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.left).unwrap()), self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.right).unwrap())) },
-            "!||" => { return boolean::NOR(self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.left).unwrap()), self.evaluate(&<Option<Box<ASTNode>> as Clone>::clone(&node.right).unwrap())) }
+    }
+}
+
+impl fmt::Display for ASTNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::latexIdentifier;
+
+    #[test]
+    fn onlyIndicesBecomeSubscripts() {
+        assert_eq!(latexIdentifier("x[12]"), "x[12]");
+        assert_eq!(latexIdentifier("x_12"), "x_{12}");
+        assert_eq!(latexIdentifier("p1"), "p1");
+        assert_eq!(latexIdentifier("x_{max}"), "x_{max}");
+        assert_eq!(latexIdentifier("req_ok"), "req\\_ok");
+        assert_eq!(latexIdentifier("is_valid_input"), "is\\_valid\\_input");
+        assert_eq!(latexIdentifier("is_valid_2"), "is\\_valid_{2}");
+    }
+}
+
+// Hilfen für die Tests der Untermodule
+#[cfg(test)]
+pub mod testing {
+    use std::collections::HashMap;
+    use super::ASTNode;
+    use super::diagnostic::SyntaxError;
+    use super::parser::Parser;
+    use super::tokenizer::{Notation, Tokenizer};
+
+    pub fn parse(formula: &str) -> Result<ASTNode, Vec<SyntaxError>> {
+        let mut tokenizer = Tokenizer::withNotation(format!("[{}]", formula), Notation::DEFAULT);
+        tokenizer.tokenize();
+        Parser::new(&tokenizer.tokens[0].expression).parse()
+    }
+
+    pub fn variables(node: &ASTNode, identifiers: &mut Vec<String>) {
+        match node {
+            ASTNode::VARIABLE(identifier) if !identifiers.contains(identifier) => identifiers.push(identifier.clone()),
+            ASTNode::NOT(operand) => variables(operand, identifiers),
+            ASTNode::BINARY(_, left, right) => {
+                variables(left, identifiers);
+                variables(right, identifiers);
+            }
             _ => {}
         }
-        return "".to_string();
+    }
+
+    // Alle Belegungen der Variablen in der Reihenfolge der Wahrheitswertetabelle
+    pub fn rows(identifiers: &Vec<String>) -> Vec<HashMap<String, bool>> {
+        (0..(1usize << identifiers.len())).map(|row| {
+            identifiers.iter().enumerate().map(|(i, identifier)| (identifier.clone(), (row >> i) & 1 == 1)).collect()
+        }).collect()
+    }
+
+    pub fn evaluate(node: &ASTNode, idToBool: &HashMap<String, bool>) -> bool {
+        node.evaluate(idToBool).unwrap()
     }
 }
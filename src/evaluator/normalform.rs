@@ -0,0 +1,318 @@
+use std::collections::HashSet;
+use super::{profile, solver, ASTNode, Operator};
+
+pub const maxClauses: usize = 4096;
+
+// Ein Literal ist eine Variable mit ihrer Polarität (true = nicht negiert)
+pub type Literal = (String, bool);
+pub type Clause = Vec<Literal>;
+
+fn not(operand: ASTNode) -> ASTNode {
+    ASTNode::NOT(Box::new(operand))
+}
+
+fn and(left: ASTNode, right: ASTNode) -> ASTNode {
+    ASTNode::BINARY(Operator::AND, Box::new(left), Box::new(right))
+}
+
+fn or(left: ASTNode, right: ASTNode) -> ASTNode {
+    ASTNode::BINARY(Operator::OR, Box::new(left), Box::new(right))
+}
+
+// Ersetzt alle Operatoren durch !, && und ||
+fn eliminate(tree: &ASTNode) -> ASTNode {
+    match tree {
+        ASTNode::NOT(operand) => not(eliminate(operand)),
+        ASTNode::BINARY(operator, left, right) => {
+            let a = eliminate(left);
+            let b = eliminate(right);
+            match operator {
+                Operator::AND => and(a, b),
+                Operator::OR => or(a, b),
+                Operator::IMPLIES => or(not(a), b),
+                Operator::CONVERSE => or(a, not(b)),
+                Operator::EQUIVALENCE => and(or(not(a.clone()), b.clone()), or(a, not(b))),
+                Operator::XOR => and(or(a.clone(), b.clone()), or(not(a), not(b))),
+                Operator::NAND => not(and(a, b)),
+                Operator::NOR => not(or(a, b))
+            }
+        }
+        _ => tree.clone()
+    }
+}
+
+// Größe des Baumes nach eliminate, ohne ihn aufzubauen (<-> und ^ verdoppeln ihre Operanden)
+fn eliminatedSize(tree: &ASTNode) -> usize {
+    match tree {
+        ASTNode::NOT(operand) => eliminatedSize(operand).saturating_add(1),
+        ASTNode::BINARY(operator, left, right) => {
+            let operands = eliminatedSize(left).saturating_add(eliminatedSize(right));
+            match operator {
+                Operator::EQUIVALENCE | Operator::XOR => operands.saturating_mul(2).saturating_add(5),
+                _ => operands.saturating_add(2)
+            }
+        }
+        _ => 1
+    }
+}
+
+// Zieht die Negationen bis vor die Variablen (Negationsnormalform)
+fn pushNegations(tree: &ASTNode, negated: bool) -> ASTNode {
+    match tree {
+        ASTNode::NOT(operand) => pushNegations(operand, !negated),
+        ASTNode::BINARY(operator, left, right) => {
+            let a = pushNegations(left, negated);
+            let b = pushNegations(right, negated);
+            if (*operator == Operator::AND) != negated { and(a, b) } else { or(a, b) }
+        }
+        _ if negated => not(tree.clone()),
+        _ => tree.clone()
+    }
+}
+
+fn normalizeClause(mut clause: Clause) -> Option<Clause> {
+    clause.sort();
+    clause.dedup();
+    for pair in clause.windows(2) {
+        if pair[0].0 == pair[1].0 {
+            return None;
+        }
+    }
+    Some(clause)
+}
+
+fn distribute(tree: &ASTNode) -> Option<Vec<Clause>> {
+    match tree {
+        ASTNode::VARIABLE(identifier) => Some(vec![vec![(identifier.clone(), true)]]),
+        ASTNode::NOT(operand) => match operand.as_ref() {
+            ASTNode::VARIABLE(identifier) => Some(vec![vec![(identifier.clone(), false)]]),
+            _ => None
+        },
+        ASTNode::BINARY(Operator::AND, left, right) => {
+            let mut clauses = distribute(left)?;
+            clauses.extend(distribute(right)?);
+            if clauses.len() > maxClauses {
+                return None;
+            }
+            Some(clauses)
+        }
+        ASTNode::BINARY(_, left, right) => {
+            let left = distribute(left)?;
+            let right = distribute(right)?;
+            if left.len() * right.len() > maxClauses {
+                return None;
+            }
+
+            let mut clauses: Vec<Clause> = vec![];
+            for a in &left {
+                for b in &right {
+                    if let Some(clause) = normalizeClause([a.clone(), b.clone()].concat()) {
+                        if !clauses.contains(&clause) {
+                            clauses.push(clause);
+                        }
+                    }
+                }
+            }
+            Some(clauses)
+        }
+        ASTNode::CONSTANT(_) => None
+    }
+}
+
+// KNF durch Umformen des Baumes. Ergibt None, wenn die Formel beim Ausmultiplizieren mehr als
+// maxClauses Klauseln bekommen würde
+pub fn rewriteCNF(tree: &ASTNode) -> Option<Vec<Clause>> {
+    let tree = solver::simplify(tree);
+    if let ASTNode::CONSTANT(value) = tree {
+        return Some(constantClauses(value));
+    }
+
+    if eliminatedSize(&tree) > maxClauses * 16 {
+        return None;
+    }
+
+    let clauses = distribute(&pushNegations(&eliminate(&tree), false))?;
+    let mut result: Vec<Clause> = vec![];
+    for clause in clauses {
+        if let Some(clause) = normalizeClause(clause) {
+            if !result.contains(&clause) {
+                result.push(clause);
+            }
+        }
+    }
+    Some(result)
+}
+
+// Die wahre Formel ist die leere Konjunktion, die falsche enthält die leere Klausel
+fn constantClauses(value: bool) -> Vec<Clause> {
+    if value { vec![] } else { vec![vec![]] }
+}
+
+struct Tseitin {
+    used: HashSet<String>,
+    counter: usize,
+    auxiliary: Vec<String>,
+    clauses: Vec<Clause>
+}
+
+impl Tseitin {
+    fn fresh(&mut self) -> String {
+        loop {
+            self.counter += 1;
+            let name = format!("t{}", self.counter);
+            if !self.used.contains(&name) {
+                self.auxiliary.push(name.clone());
+                return name;
+            }
+        }
+    }
+
+    fn encode(&mut self, tree: &ASTNode) -> Literal {
+        match tree {
+            ASTNode::NOT(operand) => {
+                let (name, positive) = self.encode(operand);
+                (name, !positive)
+            }
+            ASTNode::BINARY(operator, left, right) => {
+                let a = self.encode(left);
+                let b = self.encode(right);
+                let t: Literal = (self.fresh(), true);
+                let n = |literal: &Literal| (literal.0.clone(), !literal.1);
+
+                // Klauseln für t <-> (a op b)
+                let clauses: Vec<Clause> = match operator {
+                    Operator::AND => vec![vec![n(&t), a.clone()], vec![n(&t), b.clone()], vec![t.clone(), n(&a), n(&b)]],
+                    Operator::OR => vec![vec![t.clone(), n(&a)], vec![t.clone(), n(&b)], vec![n(&t), a.clone(), b.clone()]],
+                    Operator::IMPLIES => vec![vec![t.clone(), a.clone()], vec![t.clone(), n(&b)], vec![n(&t), n(&a), b.clone()]],
+                    Operator::CONVERSE => vec![vec![t.clone(), n(&a)], vec![t.clone(), b.clone()], vec![n(&t), a.clone(), n(&b)]],
+                    Operator::EQUIVALENCE => vec![vec![n(&t), n(&a), b.clone()], vec![n(&t), a.clone(), n(&b)], vec![t.clone(), a.clone(), b.clone()], vec![t.clone(), n(&a), n(&b)]],
+                    Operator::XOR => vec![vec![n(&t), a.clone(), b.clone()], vec![n(&t), n(&a), n(&b)], vec![t.clone(), n(&a), b.clone()], vec![t.clone(), a.clone(), n(&b)]],
+                    Operator::NAND => vec![vec![t.clone(), a.clone()], vec![t.clone(), b.clone()], vec![n(&t), n(&a), n(&b)]],
+                    Operator::NOR => vec![vec![n(&t), n(&a)], vec![n(&t), n(&b)], vec![t.clone(), a.clone(), b.clone()]]
+                };
+                for clause in clauses {
+                    if let Some(clause) = normalizeClause(clause) {
+                        self.clauses.push(clause);
+                    }
+                }
+                t
+            }
+            ASTNode::VARIABLE(identifier) => (identifier.clone(), true),
+            ASTNode::CONSTANT(value) => {
+                let t: Literal = (self.fresh(), *value);
+                self.clauses.push(vec![t.clone()]);
+                t
+            }
+        }
+    }
+}
+
+// Erfüllbarkeitsäquivalente KNF nach Tseitin: jede zusammengesetzte Teilformel bekommt eine
+// Hilfsvariable, wodurch die KNF linear in der Größe der Formel bleibt. Gibt die Klauseln und die
+// Namen der Hilfsvariablen zurück
+pub fn tseitinCNF(tree: &ASTNode, identifiers: &Vec<String>) -> (Vec<Clause>, Vec<String>) {
+    let tree = solver::simplify(tree);
+    if let ASTNode::CONSTANT(value) = tree {
+        return (constantClauses(value), vec![]);
+    }
+
+    let mut tseitin = Tseitin {
+        used: identifiers.iter().cloned().collect(),
+        counter: 0,
+        auxiliary: vec![],
+        clauses: vec![]
+    };
+    let root = tseitin.encode(&tree);
+    tseitin.clauses.insert(0, vec![root]);
+    (tseitin.clauses, tseitin.auxiliary)
+}
+
+pub fn formatClauses(clauses: &Vec<Clause>) -> String {
+    if clauses.is_empty() {
+        return "1".to_string();
+    }
+
+    let single = clauses.len() == 1;
+    let formatted: Vec<String> = clauses.iter().map(|clause| {
+        let literals: Vec<String> = clause.iter().map(|(name, positive)| {
+            if *positive { name.clone() } else { format!("!{}", name) }
+        }).collect();
+
+        if literals.is_empty() {
+            "0".to_string()
+        } else if literals.len() > 1 && !single {
+            format!("({})", profile::joinOperands(&literals, " || "))
+        } else {
+            profile::joinOperands(&literals, " || ")
+        }
+    }).collect();
+    profile::joinOperands(&formatted, " && ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::evaluator::testing::{evaluate, parse, rows, variables};
+
+    static formulas: [&str; 8] = [
+        "a && b || !c",
+        "a -> b -> c",
+        "(a <-> b) ^ c",
+        "!(a !&& b) || (c !|| a)",
+        "a <- b && !(c -> a)",
+        "a && !a",
+        "a || !a",
+        "(a ^ b) && (b ^ c) && (a ^ c)"
+    ];
+
+    fn satisfies(clauses: &Vec<Clause>, idToBool: &HashMap<String, bool>) -> bool {
+        clauses.iter().all(|clause| clause.iter().any(|(name, positive)| idToBool[name] == *positive))
+    }
+
+    #[test]
+    fn rewriteIsEquivalent() {
+        let _guard = profile::testGuard();
+        for formula in formulas {
+            let root = parse(formula).unwrap();
+            let clauses = rewriteCNF(&root).unwrap();
+            let reparsed = parse(&formatClauses(&clauses)).unwrap();
+            let mut identifiers = vec![];
+            variables(&root, &mut identifiers);
+            for row in rows(&identifiers) {
+                assert_eq!(satisfies(&clauses, &row), evaluate(&root, &row), "{}", formula);
+                assert_eq!(evaluate(&reparsed, &row), evaluate(&root, &row), "{}", formula);
+            }
+        }
+    }
+
+    #[test]
+    fn rewriteStopsAtMaxClauses() {
+        let _guard = profile::testGuard();
+        let terms: Vec<String> = (1..=13).map(|i| format!("(a{0} && b{0})", i)).collect();
+        assert_eq!(rewriteCNF(&parse(&terms.join(" || ")).unwrap()), None);
+    }
+
+    // Unter jeder Belegung der ursprünglichen Variablen sind die Klauseln genau dann erfüllbar, wenn
+    // die Formel wahr ist. Daraus folgt die Erfüllbarkeitsäquivalenz
+    #[test]
+    fn tseitinIsEquisatisfiable() {
+        let _guard = profile::testGuard();
+        for formula in formulas {
+            let root = parse(formula).unwrap();
+            let mut identifiers = vec![];
+            variables(&root, &mut identifiers);
+            let (clauses, auxiliary) = tseitinCNF(&root, &identifiers);
+            assert!(auxiliary.iter().all(|name| !identifiers.contains(name)), "{}", formula);
+
+            for row in rows(&identifiers) {
+                let extendable = rows(&auxiliary).into_iter().any(|extension| {
+                    let mut full = row.clone();
+                    full.extend(extension);
+                    satisfies(&clauses, &full)
+                });
+                assert_eq!(extendable, evaluate(&root, &row), "{}", formula);
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use super::diagnostic::{SyntaxError, SyntaxErrorKind};
+use super::tokenizer::{Associativity, Token, TokenType};
+use super::{profile, ASTNode, Operator};
+
+static operandExpected: &str = "eine Variable, 0, 1, !, ( oder wenn";
+
+// Precedence Climbing: parseExpression(maxPrec) liest einen Ausdruck, dessen Operatoren höchstens
+// die Präzedenz maxPrec haben (kleinere Zahl = bindet stärker, siehe profile::Profile).
+// Nach einem Fehler wird weitergelesen, damit alle Fehler der Zeile gemeldet werden. Fehlende
+// Operanden werden dabei durch einen Platzhalter ersetzt
+pub struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    errors: Vec<SyntaxError>
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(tokens: &'a [Token]) -> Parser<'a> {
+        Parser { tokens, position: 0, errors: vec![] }
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position).filter(|token| token.tType != TokenType::EOL)
+    }
+
+    fn advance(&mut self) -> Option<&'a Token> {
+        let token = self.peek();
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    // Position des aktuellen Tokens, am Ende die des EOL-Tokens
+    fn span(&self) -> (usize, usize) {
+        match self.tokens.get(self.position).or(self.tokens.last()) {
+            Some(token) => (token.start, token.end),
+            None => (0, 0)
+        }
+    }
+
+    fn unexpected(token: &Token, expected: &str) -> SyntaxError {
+        if token.tType == TokenType::ERROR {
+            return SyntaxError::unknown(&token.content, token.start, token.end);
+        }
+        if token.tType == TokenType::RANGE {
+            return SyntaxError::new(SyntaxErrorKind::UNEXPECTEDTOKEN(token.content.clone()), token.start, token.end, "ein einzelnes Element wie x[1], Bereiche gibt es nur in der Belegung");
+        }
+        SyntaxError::new(SyntaxErrorKind::UNEXPECTEDTOKEN(token.content.clone()), token.start, token.end, expected)
+    }
+
+    fn startsOperand(token: &Token) -> bool {
+        matches!(token.tType, TokenType::NOT | TokenType::BOOL | TokenType::IDENTIFIER | TokenType::LPAREN | TokenType::IF)
+    }
+
+    fn placeholder() -> ASTNode {
+        ASTNode::CONSTANT(false)
+    }
+
+    pub fn parse(&mut self) -> Result<ASTNode, Vec<SyntaxError>> {
+        let root = self.parseSequence(false);
+        if self.errors.is_empty() {
+            Ok(root)
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    // Liest Ausdrücke bis zum Ende bzw. bis zur schließenden Klammer. Was nach einem vollständigen
+    // Ausdruck noch übrig ist, wird gemeldet und übersprungen
+    fn parseSequence(&mut self, inParens: bool) -> ASTNode {
+        let mut root = self.parseExpression(u8::MAX);
+        loop {
+            match self.peek() {
+                None => { return root; }
+                Some(token) if token.tType == TokenType::RPAREN => {
+                    if inParens {
+                        return root;
+                    }
+                    self.errors.push(SyntaxError::new(SyntaxErrorKind::UNOPENEDPAREN, token.start, token.end, "ein Operator"));
+                    self.advance();
+                }
+                Some(token) => {
+                    self.errors.push(Parser::unexpected(token, if inParens { "ein Operator oder )" } else { "ein Operator" }));
+                    // Ein überzähliger Operand wird mitgelesen, sonst das Zeichen und ein darauf folgender Operand
+                    if !Parser::startsOperand(token) {
+                        self.advance();
+                    }
+                    if self.peek().is_some_and(Parser::startsOperand) {
+                        self.parseUnary();
+                    }
+                }
+            }
+            root = self.parseOperators(root, u8::MAX);
+        }
+    }
+
+    fn parseExpression(&mut self, maxPrec: u8) -> ASTNode {
+        let left = self.parseUnary();
+        self.parseOperators(left, maxPrec)
+    }
+
+    fn parseOperators(&mut self, mut left: ASTNode, maxPrec: u8) -> ASTNode {
+        let strict = profile::isStrict();
+        let mut combined = false;
+
+        while let Some(token) = self.peek() {
+            // Ein unbekannter Operator wie => wird gemeldet und wie ein Operator behandelt, der nur
+            // den nächsten Operanden bindet
+            if token.tType == TokenType::ERROR {
+                self.errors.push(Parser::unexpected(token, "ein Operator"));
+                self.advance();
+                if self.peek().is_some_and(Parser::startsOperand) {
+                    self.parseExpression(0);
+                }
+                continue;
+            }
+
+            let operator = match Operator::fromTokenType(&token.tType) {
+                Some(operator) => operator,
+                None => break
+            };
+            let prec = operator.precedence();
+            if prec > maxPrec {
+                break;
+            }
+            if strict && combined {
+                self.errors.push(SyntaxError::new(SyntaxErrorKind::AMBIGUOUS(token.content.clone()), token.start, token.end, "eine Klammer um die vorherige Verknüpfung"));
+            }
+            self.advance();
+
+            // Rechtsassoziative Operatoren dürfen rechts noch einmal auftauchen, linksassoziative nicht.
+            // Im strikten Profil ist rechts nur ein einzelner Operand erlaubt
+            let rightMax = if strict {
+                0
+            } else if operator.associativity() == Associativity::RIGHT {
+                prec
+            } else {
+                prec.saturating_sub(1)
+            };
+            let right = self.parseExpression(rightMax);
+            left = ASTNode::BINARY(operator, Box::new(left), Box::new(right));
+            combined = true;
+        }
+        left
+    }
+
+    fn parseUnary(&mut self) -> ASTNode {
+        // Unbekannte Zeichen werden übersprungen. Fehlt danach der Operand, wurde das schon gemeldet
+        let mut skipped = false;
+        while let Some(token) = self.peek().filter(|token| token.tType == TokenType::ERROR) {
+            self.errors.push(Parser::unexpected(token, operandExpected));
+            self.advance();
+            skipped = true;
+        }
+
+        let (start, end) = self.span();
+        let token = match self.peek() {
+            Some(token) if Parser::startsOperand(token) => token,
+            Some(token) if !skipped && token.tType != TokenType::RPAREN && Operator::fromTokenType(&token.tType).is_none() => {
+                self.errors.push(Parser::unexpected(token, operandExpected));
+                self.advance();
+                return Parser::placeholder();
+            }
+            // Fehlender Operand, z.B. in a && && b oder (a ||). Der Operator bzw. die Klammer bleibt stehen
+            _ => {
+                if !skipped {
+                    self.errors.push(SyntaxError::new(SyntaxErrorKind::MISSINGOPERAND, start, end, operandExpected));
+                }
+                return Parser::placeholder();
+            }
+        };
+        self.advance();
+
+        let mut node = match token.tType {
+            TokenType::NOT => {
+                let prec = profile::precedence(&TokenType::NOT);
+                ASTNode::NOT(Box::new(self.parseExpression(prec.saturating_sub(1))))
+            }
+            // wenn a dann b ist a -> b, wobei b so weit reicht wie die rechte Seite von ->
+            TokenType::IF => {
+                let condition = self.parseExpression(u8::MAX);
+                match self.peek() {
+                    Some(then) if then.tType == TokenType::THEN => { self.advance(); }
+                    _ => {
+                        self.errors.push(SyntaxError::new(SyntaxErrorKind::MISSINGTHEN, token.start, token.end, "dann nach der Bedingung"));
+                        return condition;
+                    }
+                }
+                let prec = if profile::isStrict() { 0 } else { Operator::IMPLIES.precedence() };
+                let consequence = self.parseExpression(prec);
+                ASTNode::BINARY(Operator::IMPLIES, Box::new(condition), Box::new(consequence))
+            }
+            TokenType::BOOL => ASTNode::CONSTANT(token.content == "1"),
+            TokenType::IDENTIFIER => ASTNode::VARIABLE(token.content.clone()),
+            _ => {
+                let inner = self.parseSequence(true);
+                if self.advance().is_none() {
+                    self.errors.push(SyntaxError::new(SyntaxErrorKind::UNCLOSEDPAREN, token.start, token.end, "eine schließende Klammer )"));
+                }
+                inner
+            }
+        };
+
+        // Ein nachgestelltes ' negiert wie in der Schaltalgebra nur den Operanden davor
+        while self.peek().is_some_and(|token| token.tType == TokenType::PRIME) {
+            self.advance();
+            node = ASTNode::NOT(Box::new(node));
+        }
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluator::testing::parse;
+
+    fn sameTree(formula: &str, bracketed: &str) {
+        assert_eq!(parse(formula).unwrap(), parse(bracketed).unwrap(), "{}", formula);
+    }
+
+    // Die Positionen beziehen sich auf die Zeile [formula], also um eins verschoben
+    fn errors(formula: &str) -> Vec<(SyntaxErrorKind, usize, usize)> {
+        parse(formula).unwrap_err().into_iter().map(|error| (error.kind, error.start, error.end)).collect()
+    }
+
+    #[test]
+    fn precedenceAndAssociativity() {
+        let _guard = profile::testGuard();
+        sameTree("a -> b -> c", "a -> (b -> c)");
+        sameTree("a <- b <- c", "(a <- b) <- c");
+        sameTree("!a && b", "(!a) && b");
+        sameTree("!!a", "!(!a)");
+        sameTree("a&&!b", "a && (!b)");
+        sameTree("a || b && c", "a || (b && c)");
+        sameTree("a && b ^ c", "a && (b ^ c)");
+        sameTree("a <-> b -> c || d", "a <-> (b -> (c || d))");
+        sameTree("wenn a dann b -> c", "a -> (b -> c)");
+        assert_eq!(parse("a -> b").unwrap(), ASTNode::BINARY(Operator::IMPLIES, Box::new(ASTNode::VARIABLE("a".to_string())), Box::new(ASTNode::VARIABLE("b".to_string()))));
+    }
+
+    #[test]
+    fn strictRejectsUnbracketedChains() {
+        let _guard = profile::testGuard();
+        profile::select(profile::Profile::strict());
+        assert_eq!(errors("a && b || c"), vec![(SyntaxErrorKind::AMBIGUOUS("||".to_string()), 8, 9)]);
+        assert_eq!(errors("a -> b -> c"), vec![(SyntaxErrorKind::AMBIGUOUS("->".to_string()), 8, 9)]);
+        sameTree("(a && b) || c", "(a && b) || c");
+        sameTree("!a && b", "(!a) && b");
+    }
+
+    #[test]
+    fn errorSpansAndRecovery() {
+        let _guard = profile::testGuard();
+        assert_eq!(errors("a && && b"), vec![(SyntaxErrorKind::MISSINGOPERAND, 6, 7)]);
+        assert_eq!(errors("a => b || (c"), vec![(SyntaxErrorKind::UNKNOWNOPERATOR("=>".to_string()), 3, 4), (SyntaxErrorKind::UNCLOSEDPAREN, 11, 11)]);
+        assert_eq!(errors("(a || b)) && c"), vec![(SyntaxErrorKind::UNOPENEDPAREN, 9, 9)]);
+        assert_eq!(errors("a b"), vec![(SyntaxErrorKind::UNEXPECTEDTOKEN("b".to_string()), 3, 3)]);
+        assert_eq!(errors("a && $ b"), vec![(SyntaxErrorKind::UNKNOWNCHARACTER("$".to_string()), 6, 6)]);
+        assert_eq!(errors("wenn a b").len(), 2);
+        // Nach einem Fehler wird weitergelesen, jeder Fehler wird einmal gemeldet
+        assert_eq!(errors("a && || b -> ) && (c"), vec![
+            (SyntaxErrorKind::MISSINGOPERAND, 6, 7),
+            (SyntaxErrorKind::MISSINGOPERAND, 14, 14),
+            (SyntaxErrorKind::UNOPENEDPAREN, 14, 14),
+            (SyntaxErrorKind::UNCLOSEDPAREN, 19, 19)
+        ]);
+    }
+}
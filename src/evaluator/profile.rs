@@ -0,0 +1,142 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use super::tokenizer::{associativities, operatorTypes, precedences, Associativity, TokenType};
+use super::Operator;
+
+#[derive(Clone, Debug)]
+pub struct Profile {
+    pub name: String,
+    pub precedences: HashMap<TokenType, u8>,
+    pub associativities: HashMap<TokenType, Associativity>,
+    // Im strikten Profil muss jede Verknüpfung innerhalb einer anderen geklammert werden
+    pub strict: bool
+}
+
+static active: Lazy<RwLock<Profile>> = Lazy::new(|| RwLock::new(Profile::standard()));
+static customProfile: Lazy<RwLock<Option<Profile>>> = Lazy::new(|| RwLock::new(None));
+
+// Das Profil gilt für die ganze Sitzung. Tests, die parsen oder das Profil wechseln, laufen deshalb
+// nacheinander und beginnen mit dem Profil default
+#[cfg(test)]
+static testLock: Lazy<std::sync::Mutex<()>> = Lazy::new(|| std::sync::Mutex::new(()));
+
+#[cfg(test)]
+pub fn testGuard() -> std::sync::MutexGuard<'static, ()> {
+    let guard = testLock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    select(Profile::standard());
+    guard
+}
+
+impl Profile {
+    pub fn standard() -> Profile {
+        Profile {
+            name: "default".to_string(),
+            precedences: precedences.clone(),
+            associativities: associativities.clone(),
+            strict: false
+        }
+    }
+
+    pub fn strict() -> Profile {
+        Profile {
+            name: "strict".to_string(),
+            strict: true,
+            ..Profile::standard()
+        }
+    }
+
+    // Eine Stufe je Eintrag, von der am stärksten zur am schwächsten bindenden. ! bindet immer am stärksten.
+    // Die Assoziativität gilt je Operator, damit z.B. -> und <- auf einer Stufe verschieden gruppieren können
+    pub fn custom(levels: &Vec<Vec<(Operator, Associativity)>>) -> Result<Profile, String> {
+        let mut profile = Profile {
+            name: "custom".to_string(),
+            precedences: HashMap::from([(TokenType::NOT, 1)]),
+            associativities: HashMap::new(),
+            strict: false
+        };
+
+        for (i, operators) in levels.iter().enumerate() {
+            for (operator, associativity) in operators {
+                if profile.precedences.contains_key(&operator.tokenType()) {
+                    return Err(format!("Der Operator {} wurde mehreren Stufen zugeordnet", operator.symbol()));
+                }
+                profile.precedences.insert(operator.tokenType(), i as u8 + 2);
+                profile.associativities.insert(operator.tokenType(), *associativity);
+            }
+        }
+
+        for tType in operatorTypes.iter() {
+            if let Some(operator) = Operator::fromTokenType(tType) {
+                if !profile.precedences.contains_key(tType) {
+                    return Err(format!("Der Operator {} wurde keiner Stufe zugeordnet", operator.symbol()));
+                }
+            }
+        }
+        Ok(profile)
+    }
+
+    // Die zweistelligen Operatoren nach Stufe und Assoziativität gruppiert, beginnend mit der am stärksten
+    // bindenden Stufe. Gruppieren Operatoren einer Stufe verschieden, gibt es für die Stufe mehrere Einträge
+    pub fn levels(&self) -> Vec<(u8, Vec<Operator>, Associativity)> {
+        let mut levels: Vec<(u8, Vec<Operator>, Associativity)> = vec![];
+        for tType in operatorTypes.iter() {
+            let operator = match Operator::fromTokenType(tType) {
+                Some(operator) => operator,
+                None => { continue; }
+            };
+            let precedence = self.precedences[tType];
+            let associativity = self.associativities.get(tType).copied().unwrap_or(Associativity::LEFT);
+            match levels.iter_mut().find(|level| level.0 == precedence && level.2 == associativity) {
+                Some(level) => level.1.push(operator),
+                None => levels.push((precedence, vec![operator], associativity))
+            }
+        }
+        levels.sort_by_key(|level| level.0);
+        levels
+    }
+}
+
+pub fn getActive() -> Profile {
+    active.read().unwrap().clone()
+}
+
+pub fn select(profile: Profile) {
+    if profile.name == "custom" {
+        *customProfile.write().unwrap() = Some(profile.clone());
+    }
+    *active.write().unwrap() = profile;
+}
+
+pub fn getCustom() -> Option<Profile> {
+    customProfile.read().unwrap().clone()
+}
+
+pub fn precedence(tType: &TokenType) -> u8 {
+    active.read().unwrap().precedences.get(tType).copied().unwrap_or(0)
+}
+
+pub fn associativity(tType: &TokenType) -> Associativity {
+    active.read().unwrap().associativities.get(tType).copied().unwrap_or(Associativity::LEFT)
+}
+
+pub fn isStrict() -> bool {
+    active.read().unwrap().strict
+}
+
+// Verbindet die Operanden mit dem Operator, im strikten Profil paarweise geklammert
+pub fn joinOperands(operands: &Vec<String>, operator: &str) -> String {
+    if !isStrict() {
+        return operands.join(operator);
+    }
+
+    let mut joined = String::new();
+    for (i, operand) in operands.iter().enumerate() {
+        joined = match i {
+            0 => operand.clone(),
+            1 => format!("{}{}{}", joined, operator, operand),
+            _ => format!("({}){}{}", joined, operator, operand)
+        };
+    }
+    joined
+}
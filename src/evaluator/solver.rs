@@ -0,0 +1,318 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use super::{boolean, ASTNode, Operator};
+
+fn notNode(node: ASTNode) -> ASTNode {
+    match node {
+        ASTNode::CONSTANT(value) => ASTNode::CONSTANT(boolean::NOT(value)),
+        ASTNode::NOT(operand) => *operand,
+        _ => ASTNode::NOT(Box::new(node))
+    }
+}
+
+pub fn assign(node: &ASTNode, idToBool: &HashMap<String, bool>) -> ASTNode {
+    match node {
+        ASTNode::VARIABLE(identifier) => match idToBool.get(identifier) {
+            Some(value) => ASTNode::CONSTANT(*value),
+            None => node.clone()
+        },
+        ASTNode::CONSTANT(_) => node.clone(),
+        ASTNode::NOT(operand) => ASTNode::NOT(Box::new(assign(operand, idToBool))),
+        ASTNode::BINARY(operator, left, right) => ASTNode::BINARY(*operator, Box::new(assign(left, idToBool)), Box::new(assign(right, idToBool)))
+    }
+}
+
+pub fn simplify(node: &ASTNode) -> ASTNode {
+    match node {
+        ASTNode::NOT(operand) => notNode(simplify(operand)),
+        ASTNode::BINARY(operator, left, right) => {
+            let left = simplify(left);
+            let right = simplify(right);
+
+            // Ist eine Seite konstant, bleibt die andere Seite, ihre Negation oder eine Konstante übrig
+            let (value, other, isLeft) = match (&left, &right) {
+                (ASTNode::CONSTANT(a), ASTNode::CONSTANT(b)) => { return ASTNode::CONSTANT(operator.apply(*a, *b)); }
+                (ASTNode::CONSTANT(a), _) => (*a, right, true),
+                (_, ASTNode::CONSTANT(b)) => (*b, left, false),
+                _ => { return ASTNode::BINARY(*operator, Box::new(left), Box::new(right)); }
+            };
+
+            match operator {
+                Operator::AND => if value { other } else { ASTNode::CONSTANT(false) },
+                Operator::OR => if value { ASTNode::CONSTANT(true) } else { other },
+                Operator::IMPLIES if isLeft => if value { other } else { ASTNode::CONSTANT(true) },
+                Operator::IMPLIES => if value { ASTNode::CONSTANT(true) } else { notNode(other) },
+                Operator::CONVERSE if isLeft => if value { ASTNode::CONSTANT(true) } else { notNode(other) },
+                Operator::CONVERSE => if value { other } else { ASTNode::CONSTANT(true) },
+                Operator::EQUIVALENCE => if value { other } else { notNode(other) },
+                Operator::XOR => if value { notNode(other) } else { other },
+                Operator::NAND => if value { notNode(other) } else { ASTNode::CONSTANT(true) },
+                Operator::NOR => if value { ASTNode::CONSTANT(false) } else { notNode(other) }
+            }
+        }
+        _ => node.clone()
+    }
+}
+
+fn countIdentifiers(node: &ASTNode, counts: &mut HashMap<String, u32>) {
+    match node {
+        ASTNode::VARIABLE(identifier) => { *counts.entry(identifier.clone()).or_insert(0) += 1; }
+        ASTNode::NOT(operand) => countIdentifiers(operand, counts),
+        ASTNode::BINARY(_, left, right) => {
+            countIdentifiers(left, counts);
+            countIdentifiers(right, counts);
+        }
+        ASTNode::CONSTANT(_) => {}
+    }
+}
+
+fn pickIdentifier(node: &ASTNode) -> Option<String> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    countIdentifiers(node, &mut counts);
+    counts.into_iter().max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0))).map(|(identifier, _)| identifier)
+}
+
+// Nach dieser Zeit wird die Fallunterscheidung abgebrochen, statt die REPL beliebig lange zu blockieren
+pub const timeLimit: Duration = Duration::from_secs(5);
+
+fn tooComplex() -> String {
+    format!("Die Formel ist zu aufwendig, die Fallunterscheidung wurde nach {} Sekunden abgebrochen", timeLimit.as_secs())
+}
+
+// Fallunterscheidung (Shannon-Zerlegung) mit Vereinfachung nach jeder Belegung. Bereits untersuchte
+// Restformeln werden gemerkt, dadurch bleibt z.B. x1 ^ ... ^ xn linear statt exponentiell. Eine
+// Konjunktion oder Disjunktion aus Teilen ohne gemeinsame Variablen wird in die Teile zerlegt
+struct Solver {
+    deadline: Instant,
+    // Restformeln ohne die gesuchte Belegung
+    failed: HashSet<ASTNode>,
+    // Anzahl der Modelle einer Restformel über ihren eigenen Variablen
+    counts: HashMap<ASTNode, u128>
+}
+
+fn flatten(node: &ASTNode, operator: Operator, parts: &mut Vec<ASTNode>) {
+    match node {
+        ASTNode::BINARY(op, left, right) if *op == operator => {
+            flatten(left, operator, parts);
+            flatten(right, operator, parts);
+        }
+        _ => parts.push(node.clone())
+    }
+}
+
+fn variablesOf(node: &ASTNode) -> HashSet<String> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    countIdentifiers(node, &mut counts);
+    counts.into_keys().collect()
+}
+
+// Zerlegt eine Kette aus && bzw. || in Teilformeln ohne gemeinsame Variablen
+fn components(node: &ASTNode) -> Option<(Operator, Vec<ASTNode>)> {
+    let operator = match node {
+        ASTNode::BINARY(operator @ (Operator::AND | Operator::OR), _, _) => *operator,
+        _ => { return None; }
+    };
+    let mut parts: Vec<ASTNode> = vec![];
+    flatten(node, operator, &mut parts);
+
+    let mut groups: Vec<(Vec<ASTNode>, HashSet<String>)> = vec![];
+    for part in parts {
+        let mut variables = variablesOf(&part);
+        let mut members = vec![part];
+        let mut i = 0;
+        while i < groups.len() {
+            if groups[i].1.is_disjoint(&variables) {
+                i += 1;
+                continue;
+            }
+            let (otherMembers, otherVariables) = groups.remove(i);
+            members.extend(otherMembers);
+            variables.extend(otherVariables);
+        }
+        groups.push((members, variables));
+    }
+    if groups.len() < 2 {
+        return None;
+    }
+
+    let joined = groups.into_iter().map(|(members, _)| {
+        members.into_iter().reduce(|left, right| ASTNode::BINARY(operator, Box::new(left), Box::new(right))).unwrap()
+    }).collect();
+    Some((operator, joined))
+}
+
+impl Solver {
+    fn new() -> Solver {
+        Solver { deadline: Instant::now() + timeLimit, failed: HashSet::new(), counts: HashMap::new() }
+    }
+
+    fn step(&self) -> Result<(), String> {
+        if Instant::now() > self.deadline {
+            return Err(tooComplex());
+        }
+        Ok(())
+    }
+
+    fn find(&mut self, node: ASTNode, wanted: bool) -> Result<Option<HashMap<String, bool>>, String> {
+        if let ASTNode::CONSTANT(value) = node {
+            return Ok(if value == wanted { Some(HashMap::new()) } else { None });
+        }
+        if self.failed.contains(&node) {
+            return Ok(None);
+        }
+        self.step()?;
+
+        // Bei a && b mit wanted = 1 (bzw. a || b mit 0) muss jeder Teil den Wert annehmen, sonst reicht einer
+        if let Some((operator, parts)) = components(&node) {
+            let every = (operator == Operator::AND) == wanted;
+            let mut result: HashMap<String, bool> = HashMap::new();
+            for part in parts {
+                match self.find(part, wanted)? {
+                    Some(partial) if every => result.extend(partial),
+                    Some(partial) => { return Ok(Some(partial)); }
+                    None if every => {
+                        self.failed.insert(node);
+                        return Ok(None);
+                    }
+                    None => {}
+                }
+            }
+            if every {
+                return Ok(Some(result));
+            }
+            self.failed.insert(node);
+            return Ok(None);
+        }
+
+        let identifier = match pickIdentifier(&node) {
+            Some(identifier) => identifier,
+            None => { return Ok(None); }
+        };
+        for value in [false, true] {
+            let idToBool = HashMap::from([(identifier.clone(), value)]);
+            if let Some(mut result) = self.find(simplify(&assign(&node, &idToBool)), wanted)? {
+                result.insert(identifier.clone(), value);
+                return Ok(Some(result));
+            }
+        }
+        self.failed.insert(node);
+        Ok(None)
+    }
+
+    fn count(&mut self, node: &ASTNode) -> Result<u128, String> {
+        if let ASTNode::CONSTANT(value) = node {
+            return Ok(if *value { 1 } else { 0 });
+        }
+        if let Some(count) = self.counts.get(node) {
+            return Ok(*count);
+        }
+        self.step()?;
+        let variables = countVariables(node);
+
+        // Bei unabhängigen Teilen multiplizieren sich die Modelle von &&, bei || die Gegenbeispiele
+        if let Some((operator, parts)) = components(node) {
+            let mut product: u128 = 1;
+            for part in parts {
+                let count = self.count(&part)?;
+                product *= if operator == Operator::AND { count } else { (1u128 << countVariables(&part)) - count };
+            }
+            let count = if operator == Operator::AND { product } else { (1u128 << variables) - product };
+            self.counts.insert(node.clone(), count);
+            return Ok(count);
+        }
+
+        let identifier = match pickIdentifier(node) {
+            Some(identifier) => identifier,
+            None => { return Ok(0); }
+        };
+        let mut count: u128 = 0;
+        for value in [false, true] {
+            let idToBool = HashMap::from([(identifier.clone(), value)]);
+            let child = simplify(&assign(node, &idToBool));
+            // Variablen, die beim Vereinfachen weggefallen sind, sind beliebig
+            count += self.count(&child)? << (variables - 1 - countVariables(&child));
+        }
+        self.counts.insert(node.clone(), count);
+        Ok(count)
+    }
+}
+
+fn countVariables(node: &ASTNode) -> u32 {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    countIdentifiers(node, &mut counts);
+    counts.len() as u32
+}
+
+// Sucht eine Belegung, unter der die Formel den Wert `wanted` annimmt. Nicht belegte Variablen sind beliebig
+pub fn findAssignment(node: &ASTNode, wanted: bool) -> Result<Option<HashMap<String, bool>>, String> {
+    Solver::new().find(simplify(node), wanted)
+}
+
+// Zählt die Belegungen der `variables` Variablen, unter denen die Formel wahr ist
+pub fn countModels(node: &ASTNode, variables: u32) -> Result<u128, String> {
+    let node = simplify(node);
+    let count = Solver::new().count(&node)?;
+    Ok(count << (variables - countVariables(&node)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluator::profile;
+    use crate::evaluator::testing::{evaluate, parse, rows, variables};
+
+    static formulas: [&str; 6] = [
+        "a && b || !c",
+        "(a -> b) <-> (!b -> !a)",
+        "(a || b) && (c || d) && (e ^ f)",
+        "a !&& (b !|| c) ^ d",
+        "(a && b) || (c && d) || !e",
+        "a && !a || b <- c"
+    ];
+
+    #[test]
+    fn countMatchesTruthTable() {
+        let _guard = profile::testGuard();
+        for formula in formulas {
+            let root = parse(formula).unwrap();
+            let mut identifiers = vec![];
+            variables(&root, &mut identifiers);
+            let expected = rows(&identifiers).iter().filter(|row| evaluate(&root, row)).count() as u128;
+            assert_eq!(countModels(&root, identifiers.len() as u32 + 2), Ok(expected << 2), "{}", formula);
+        }
+    }
+
+    #[test]
+    fn assignmentsAreModels() {
+        let _guard = profile::testGuard();
+        for formula in formulas {
+            let root = parse(formula).unwrap();
+            let mut identifiers = vec![];
+            variables(&root, &mut identifiers);
+            for wanted in [false, true] {
+                match findAssignment(&root, wanted).unwrap() {
+                    Some(idToBool) => {
+                        // Nicht belegte Variablen sind beliebig
+                        let mut full: HashMap<String, bool> = identifiers.iter().map(|identifier| (identifier.clone(), false)).collect();
+                        full.extend(idToBool);
+                        assert_eq!(evaluate(&root, &full), wanted, "{}", formula);
+                    }
+                    None => assert!(rows(&identifiers).iter().all(|row| evaluate(&root, row) != wanted), "{}", formula)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn largeFormulasFinishQuickly() {
+        let _guard = profile::testGuard();
+        let chain: Vec<String> = (1..=60).map(|i| format!("x{}", i)).collect();
+        let xor = parse(&chain.join(" ^ ")).unwrap();
+        assert_eq!(countModels(&xor, 60), Ok(1u128 << 59));
+        let tautology = parse(&format!("({0}) || !({0})", chain.join(" ^ "))).unwrap();
+        assert_eq!(findAssignment(&tautology, false), Ok(None));
+
+        let clauses: Vec<String> = (1..=40).map(|i| format!("(a{0} || b{0})", i)).collect();
+        assert_eq!(countModels(&parse(&clauses.join(" && ")).unwrap(), 80), Ok(3u128.pow(40)));
+    }
+}
@@ -1,9 +1,11 @@
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
-use std::{fmt, usize};
+use std::fmt;
+use std::sync::RwLock;
+use super::diagnostic::{SyntaxError, SyntaxErrorKind};
 
-static operators: [&str; 9] = ["&&", "||", "!", "->", "<-", "<->", "^", "!&&", "!||"];
-static commands: [&str; 9] = ["exit", "table", "valid", "satis", "semcons", "cnf", "dnf", "latex", "tree"];
+pub static operators: [&str; 9] = ["&&", "||", "!", "->", "<-", "<->", "^", "!&&", "!||"];
+pub static commands: [&str; 11] = ["exit", "table", "valid", "satis", "semcons", "cnf", "dnf", "latex", "tree", "profile", "notation"];
 pub static operatorTypes: [TokenType; 9] = [
     TokenType::NOT, TokenType::AND, TokenType::OR, TokenType::IMPLIES, TokenType::CONVERSE, TokenType::EQUIVALENCE, TokenType::XOR, TokenType::NAND, TokenType::NOR
 ];
@@ -22,11 +24,100 @@ static operatorMap: Lazy<HashMap<String, TokenType>> = Lazy::new(|| {
     ])
 });
 
+// Operatoren als Wörter, wenn ... dann wird vom Parser zu einer Implikation
+pub static keywordMap: Lazy<HashMap<&str, TokenType>> = Lazy::new(|| {
+    HashMap::from([
+        ("and", TokenType::AND),
+        ("or", TokenType::OR),
+        ("not", TokenType::NOT),
+        ("xor", TokenType::XOR),
+        ("nand", TokenType::NAND),
+        ("nor", TokenType::NOR),
+        ("implies", TokenType::IMPLIES),
+        ("iff", TokenType::EQUIVALENCE),
+        ("und", TokenType::AND),
+        ("oder", TokenType::OR),
+        ("nicht", TokenType::NOT),
+        ("wenn", TokenType::IF),
+        ("dann", TokenType::THEN)
+    ])
+});
+
+// Benannte Konstanten werden wie 0 und 1 gelesen, auch in der Belegung hinter einem <AUSDRUCK>
+static constantMap: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
+    HashMap::from([
+        ("true", "1"),
+        ("false", "0"),
+        ("T", "1"),
+        ("F", "0")
+    ])
+});
+
+// Zeichen aus Vorlesungsfolien und PDFs, ⊤ und ⊥ werden zu den Konstanten 1 und 0
+static symbolMap: Lazy<HashMap<char, TokenType>> = Lazy::new(|| {
+    HashMap::from([
+        ('∧', TokenType::AND),
+        ('∨', TokenType::OR),
+        ('¬', TokenType::NOT),
+        ('→', TokenType::IMPLIES),
+        ('←', TokenType::CONVERSE),
+        ('↔', TokenType::EQUIVALENCE),
+        ('⊕', TokenType::XOR),
+        ('↑', TokenType::NAND),
+        ('↓', TokenType::NOR),
+        ('⊤', TokenType::BOOL),
+        ('⊥', TokenType::BOOL)
+    ])
+});
+
+// Schaltalgebra-Schreibweise: ab' + c(d + e)' ist [a && !b || c && !(d || e)]
+static engineeringMap: Lazy<HashMap<char, TokenType>> = Lazy::new(|| {
+    HashMap::from([
+        ('+', TokenType::OR),
+        ('*', TokenType::AND),
+        ('·', TokenType::AND),
+        ('~', TokenType::NOT),
+        ('‾', TokenType::NOT),
+        ('\'', TokenType::PRIME)
+    ])
+});
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Notation {
+    DEFAULT,
+    ENGINEERING,
+}
+
+impl Notation {
+    pub fn fromName(name: &str) -> Option<Notation> {
+        match name {
+            "default" => Some(Notation::DEFAULT),
+            "engineering" => Some(Notation::ENGINEERING),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Notation::DEFAULT => "default",
+            Notation::ENGINEERING => "engineering"
+        }
+    }
+}
+
+static activeNotation: Lazy<RwLock<Notation>> = Lazy::new(|| RwLock::new(Notation::DEFAULT));
+
+pub fn getNotation() -> Notation {
+    *activeNotation.read().unwrap()
+}
+
+pub fn setNotation(notation: Notation) {
+    *activeNotation.write().unwrap() = notation;
+}
+
+// Präzedenzen und Assoziativitäten des Profils default, siehe profile::Profile
 pub static precedences: Lazy<HashMap<TokenType, u8>> = Lazy::new(|| {
     HashMap::from([
-        (TokenType::EOL, 0),
-        (TokenType::BOOL, 0),
-        (TokenType::BLOCK, 0),
         (TokenType::NOT, 1),
         (TokenType::AND, 3),
         (TokenType::OR, 4),
@@ -39,6 +130,26 @@ pub static precedences: Lazy<HashMap<TokenType, u8>> = Lazy::new(|| {
     ])
 });
 
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Associativity {
+    LEFT,
+    RIGHT,
+}
+
+// a -> b -> c wird wie in der Literatur als a -> (b -> c) gelesen, alles andere von links
+pub static associativities: Lazy<HashMap<TokenType, Associativity>> = Lazy::new(|| {
+    HashMap::from([
+        (TokenType::AND, Associativity::LEFT),
+        (TokenType::OR, Associativity::LEFT),
+        (TokenType::IMPLIES, Associativity::RIGHT),
+        (TokenType::CONVERSE, Associativity::LEFT),
+        (TokenType::EQUIVALENCE, Associativity::LEFT),
+        (TokenType::XOR, Associativity::LEFT),
+        (TokenType::NAND, Associativity::LEFT),
+        (TokenType::NOR, Associativity::LEFT)
+    ])
+});
+
 
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
 pub enum TokenType {
@@ -50,7 +161,6 @@ pub enum TokenType {
     EXPRESSION,
     LPAREN,
     RPAREN,
-    BLOCK,
 
     NOT,
     AND,
@@ -61,27 +171,39 @@ pub enum TokenType {
     XOR,
     NAND,
     NOR,
+    IF,
+    THEN,
+    PRIME,
+    RANGE,
 }
 
+// start und end sind die Positionen des ersten und letzten Zeichens in der eingegebenen Zeile
 #[derive(Clone, Debug)]
 pub struct Token {
     pub tType: TokenType,
     pub content: String,
     pub expression: Vec<Token>,
-    pub start: u16,
-    pub end: u16
+    pub start: usize,
+    pub end: usize
 }
 
 pub struct Tokenizer {
     pub line: String,
-    pub index: u16,
+    pub index: usize,
     pub tokens: Vec<Token>,
-    pub current: char
+    pub current: char,
+    pub errors: Vec<SyntaxError>,
+    // Schreibweise der Ausdrücke in eckigen Klammern, die Befehle selbst werden immer gleich gelesen
+    pub notation: Notation,
+    nested: bool,
+    // Die Zeile als Zeichen, damit index auch bei Zeichen wie ∧ (3 Bytes) eine Zeichenposition bleibt
+    chars: Vec<char>
 }
 
+#[allow(dead_code)]
 fn printTokens(tokens: &Vec<Token>) {
     for token in tokens {
-        if token.tType == TokenType::EXPRESSION || token.tType == TokenType::BLOCK {
+        if token.tType == TokenType::EXPRESSION {
             print!("[{}] [ ", token.tType);
             for tok in &token.expression {
                 print!("{} ", tok);
@@ -93,72 +215,6 @@ fn printTokens(tokens: &Vec<Token>) {
     }
 }
 
-pub fn checkSyntax(expression: &Vec<Token>) -> bool {
-    let mut i: u16 = 0;
-    let nullToken = Token { 
-        tType: TokenType::EOL, 
-        content: "".to_string(), 
-        expression: vec![], 
-        start: 0, 
-        end: 0 
-    };
-
-    while i < expression.len() as u16 {
-        let curr = &expression[i as usize];
-
-        let next = if i + 1 < expression.len() as u16 {
-            &expression[(i + 1) as usize]
-        } else {
-            &nullToken
-        };
-
-        match curr.tType {
-            TokenType::BOOL => {
-                if next.tType == TokenType::EOL || operatorTypes.contains(&next.tType) || next.tType == TokenType::RPAREN {
-                    i += 1;
-                    continue;
-                }
-                return false;
-            }
-            TokenType::IDENTIFIER => {
-                if next.tType == TokenType::EOL || operatorTypes.contains(&next.tType) || next.tType == TokenType::RPAREN {
-                    i += 1;
-                    continue;
-                }
-                return false;
-            }
-            _ if operatorTypes.contains(&curr.tType) => {
-                if (curr.tType != TokenType::NOT) && (next.tType == TokenType::BOOL || next.tType == TokenType::IDENTIFIER || operatorTypes.contains(&next.tType) || next.tType == TokenType::LPAREN) {
-                    i += 1;
-                    continue;
-                } else if (curr.tType == TokenType::NOT) && (next.tType == TokenType::BOOL || next.tType == TokenType::IDENTIFIER || next.tType == TokenType::LPAREN) {
-                    i += 1;
-                    continue;
-                }
-                return false
-            }
-            TokenType::LPAREN => {
-                if next.tType == TokenType::LPAREN || next.tType == TokenType::BOOL || next.tType == TokenType::IDENTIFIER || next.tType == TokenType::NOT {
-                    i += 1;
-                    continue;
-                }
-                return false;
-            }
-            TokenType::RPAREN => {
-                if (operatorTypes.contains(&curr.tType) && (next.tType != TokenType::NOT)) || next.tType == TokenType::RPAREN || next.tType == TokenType::EOL {
-                    i += 1;
-                    continue;
-                }
-                return false;
-            }
-            TokenType::EOL => { break }
-            _ => { return false; }
-        }
-    }
-
-    true
-}
-
 impl fmt::Display for TokenType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -170,7 +226,6 @@ impl fmt::Display for TokenType {
             TokenType::EXPRESSION => write!(f, "EXPRESSION"),
             TokenType::LPAREN => write!(f, "LPAREN"),
             TokenType::RPAREN => write!(f, "RPAREN"),
-            TokenType::BLOCK => write!(f, "BLOCK"),
             TokenType::NOT => write!(f, "NOT"),
             TokenType::AND => write!(f, "AND"),
             TokenType::OR => write!(f, "OR"),
@@ -179,38 +234,162 @@ impl fmt::Display for TokenType {
             TokenType::EQUIVALENCE => write!(f, "EQUIVALENCE"),
             TokenType::XOR => write!(f, "XOR"),
             TokenType::NAND => write!(f, "NAND"),
-            TokenType::NOR => write!(f, "NOR")
+            TokenType::NOR => write!(f, "NOR"),
+            TokenType::IF => write!(f, "IF"),
+            TokenType::THEN => write!(f, "THEN"),
+            TokenType::PRIME => write!(f, "PRIME"),
+            TokenType::RANGE => write!(f, "RANGE")
         }
     }
 }
 
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", format!("[{}] {} {:?} {}..{} ", self.tType, self.content, self.expression, self.start, self.end))
+        write!(f, "[{}] {} {:?} {}..{} ", self.tType, self.content, self.expression, self.start, self.end)
     }
 }
 
 impl Tokenizer {
-   fn forward(&mut self) {
-        if self.index < self.line.len() as u16 {
+    pub fn new(line: String) -> Tokenizer {
+        Tokenizer::withNotation(line, getNotation())
+    }
+
+    pub fn withNotation(line: String, notation: Notation) -> Tokenizer {
+        let chars: Vec<char> = line.chars().collect();
+        Tokenizer {
+            line,
+            index: 0,
+            tokens: vec![],
+            current: '\0',
+            errors: vec![],
+            notation,
+            nested: false,
+            chars
+        }
+    }
+
+    fn isEngineering(&self) -> bool {
+        self.nested && self.notation == Notation::ENGINEERING
+    }
+
+    // In der Schaltalgebra besteht eine Variable aus einem Buchstaben und optional Ziffern, damit ab
+    // als a && b gelesen werden kann
+    fn makeLetter(&mut self) -> Token {
+        let mut identifier = String::new();
+        identifier.push(self.current);
+        let start: usize = self.index;
+
+        self.forward();
+        while self.current.is_ascii_digit() {
+            identifier.push(self.current);
+            self.forward();
+        }
+        let isRange = self.makeSuffix(&mut identifier);
+
+        Token {
+            tType: if isRange { TokenType::RANGE } else { TokenType::IDENTIFIER },
+            content: identifier,
+            expression: vec![],
+            start,
+            end: self.index - 1
+        }
+    }
+
+    // Fügt zwischen zwei aufeinanderfolgenden Operanden wie in ab oder a(b + c) ein && ein
+    fn insertImplicitAnd(&mut self) {
+        let mut tokens: Vec<Token> = vec![];
+        for token in self.tokens.drain(..) {
+            let endsOperand = tokens.last().is_some_and(|last: &Token| matches!(last.tType, TokenType::IDENTIFIER | TokenType::BOOL | TokenType::RPAREN | TokenType::PRIME));
+            let startsOperand = matches!(token.tType, TokenType::IDENTIFIER | TokenType::BOOL | TokenType::LPAREN | TokenType::NOT);
+            if endsOperand && startsOperand {
+                tokens.push(Token { tType: TokenType::AND, content: "·".to_string(), expression: vec![], start: token.start, end: token.start });
+            }
+            tokens.push(token);
+        }
+        self.tokens = tokens;
+    }
+
+    fn forward(&mut self) {
+        if self.index < self.chars.len() {
             self.index += 1;
-            self.current = if self.index < self.line.len() as u16 {
-                self.line.chars().nth(self.index as usize).unwrap_or('\0')
-            } else {
-                '\0'
-            };
-        } else {
-            self.current = '\0';
         }
+        self.current = self.chars.get(self.index).copied().unwrap_or('\0');
+    }
+
+    fn charAt(&self, index: usize) -> char {
+        self.chars.get(index).copied().unwrap_or('\0')
+    }
+
+    fn skip(&mut self, count: usize) {
+        for _ in 0..count {
+            self.forward();
+        }
+    }
+
+    fn digitsFrom(&self, index: usize) -> usize {
+        let mut end = index;
+        while self.charAt(end).is_ascii_digit() {
+            end += 1;
+        }
+        end
+    }
+
+    // Liest einen Index hinter dem Namen: x_{10} wird zu x_10, x_1 bleibt, x[3] ist das Element einer
+    // Familie und x[1..3] ein Bereich davon. Gibt zurück, ob es ein Bereich war
+    fn makeSuffix(&mut self, identifier: &mut String) -> bool {
+        let i = self.index;
+        if self.current == '_' && self.charAt(i + 1) == '{' {
+            let mut end = i + 2;
+            while self.charAt(end).is_alphanumeric() {
+                end += 1;
+            }
+            // x_{10} ist dasselbe wie x_10, ein Index aus Buchstaben wie x_{max} behält die Klammern
+            if end > i + 2 && self.charAt(end) == '}' {
+                if self.chars[i + 2..end].iter().all(|c| c.is_ascii_digit()) {
+                    identifier.push('_');
+                    identifier.extend(&self.chars[i + 2..end]);
+                } else {
+                    identifier.extend(&self.chars[i..=end]);
+                }
+                self.skip(end + 1 - i);
+            }
+        } else if self.current == '_' && self.charAt(i + 1).is_ascii_digit() {
+            let end = self.digitsFrom(i + 1);
+            identifier.extend(&self.chars[i..end]);
+            self.skip(end - i);
+        }
+
+        let i = self.index;
+        if self.current != '[' {
+            return false;
+        }
+        let first = self.digitsFrom(i + 1);
+        if first == i + 1 {
+            return false;
+        }
+        let mut end = first;
+        let isRange = self.charAt(end) == '.' && self.charAt(end + 1) == '.';
+        if isRange {
+            end = self.digitsFrom(end + 2);
+            if end == first + 2 {
+                return false;
+            }
+        }
+        if self.charAt(end) != ']' {
+            return false;
+        }
+        identifier.extend(&self.chars[i..=end]);
+        self.skip(end + 1 - i);
+        isRange
     }
 
     fn makeIdentifier(&mut self) -> Token {
         let mut identifier = String::new();
         identifier.push(self.current);
-        let start: u16 = self.index;
+        let start: usize = self.index;
 
         self.forward();
-        while self.current.is_alphanumeric() {
+        while self.current.is_alphanumeric() || (self.current == '_' && self.charAt(self.index + 1) != '{') {
             identifier.push(self.current);
             self.forward();
         }
@@ -221,7 +400,37 @@ impl Tokenizer {
                 content: identifier,
                 expression: vec![],
                 start,
-                end: self.index
+                end: self.index - 1
+            }
+        }
+
+        if self.makeSuffix(&mut identifier) {
+            return Token {
+                tType: TokenType::RANGE,
+                content: identifier,
+                expression: vec![],
+                start,
+                end: self.index - 1
+            }
+        }
+
+        if let Some(value) = constantMap.get(identifier.as_str()) {
+            return Token {
+                tType: TokenType::BOOL,
+                content: value.to_string(),
+                expression: vec![],
+                start,
+                end: self.index - 1
+            }
+        }
+
+        if let Some(tType) = keywordMap.get(identifier.as_str()) {
+            return Token {
+                tType: tType.clone(),
+                content: identifier,
+                expression: vec![],
+                start,
+                end: self.index - 1
             }
         }
 
@@ -230,18 +439,23 @@ impl Tokenizer {
             content: identifier,
             expression: vec![],
             start,
-            end: self.index
+            end: self.index - 1
         }
     }
 
+    // Nimmt den längsten bekannten Operator am Anfang der Zeichenfolge, damit z.B. &&! als && ! gelesen wird.
+    // Passt kein Operator, wird die ganze Zeichenfolge zu einem Fehler
     fn makeOperator(&mut self) -> Token {
-        let mut operator = String::new();
-        operator.push(self.current);
-        let start: u16 = self.index;
+        let start: usize = self.index;
+        let mut length: usize = 0;
+        while "&|!-^<>=".contains(self.charAt(start + length)) {
+            length += 1;
+        }
+        let run: String = self.chars[start..start + length].iter().collect();
+        let length = (1..=length).rev().find(|&n| operators.contains(&&run[..n])).unwrap_or(length);
 
-        self.forward();
-        while "&|!-^<>".contains(self.current) {
-            operator.push(self.current);
+        let operator = run[..length].to_string();
+        for _ in 0..length {
             self.forward();
         }
 
@@ -260,49 +474,53 @@ impl Tokenizer {
             content: operator,
             expression: vec![],
             start,
-            end: self.index
+            end: self.index - 1
         }
     }
 
     fn makeExpression(&mut self) -> Token {
         let mut expression: Vec<Token> = vec![];
         let mut exprStr = String::new();
-        let start: u16 = self.index;
+        let start: usize = self.index;
 
         self.forward();
 
-        while self.current != ']' {
+        // Eckige Klammern im Ausdruck wie in x[3] werden mitgezählt
+        let mut depth: usize = 0;
+        while self.current != ']' || depth > 0 {
             if self.current == '\0' {
                 break;
             }
+            match self.current {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
             exprStr.push(self.current);
             self.forward()
         }
 
+        // Fehlt die schließende Klammer, wird der Rest der Zeile trotzdem als Ausdruck gelesen
         if self.current == '\0' {
-            println!("Da hat jemand die schließende Klammer von der Klammer an der Stelle {} vergessenfür.", start);
-            return Token {
-                tType: TokenType::ERROR,
-                content: exprStr,
-                expression: vec![],
-                start,
-                end: self.index
-            };
+            self.errors.push(SyntaxError::new(SyntaxErrorKind::MISSINGBRACKET, start, start, "eine schließende eckige Klammer ]"));
         }
 
-        let mut tokenizer = Tokenizer { 
-            line: exprStr, 
-            index: 0, 
-            tokens: vec![], 
-            current: '\0' 
-        };
-        
+        let mut tokenizer = Tokenizer::withNotation(exprStr, self.notation);
+        tokenizer.nested = true;
         tokenizer.tokenize();
         expression = tokenizer.tokens;
 
+        // Die Positionen im Ausdruck werden zu Positionen in der ganzen Zeile
+        shiftTokens(&mut expression, start + 1);
+        for mut error in tokenizer.errors {
+            error.start += start + 1;
+            error.end += start + 1;
+            self.errors.push(error);
+        }
+
         Token { 
             tType: TokenType::EXPRESSION, 
-            content: "".to_string(), 
+            content: tokenizer.line.trim().to_string(), 
             expression, 
             start, 
             end: self.index 
@@ -310,7 +528,7 @@ impl Tokenizer {
     }
 
     pub fn tokenize(&mut self) {
-        self.current = self.line.chars().nth(self.index as usize).unwrap();
+        self.current = self.chars.get(self.index).copied().unwrap_or('\0');
         while self.current != '\0' {
 
             if self.current.is_whitespace() {
@@ -318,7 +536,20 @@ impl Tokenizer {
                 continue;
             }
 
-            if self.current.is_alphabetic() {
+            if self.isEngineering() {
+                if let Some(tType) = engineeringMap.get(&self.current) {
+                    self.tokens.push(Token { tType: tType.clone(), content: self.current.to_string(), expression: vec![], start: self.index, end: self.index });
+                    self.forward();
+                    continue;
+                }
+                if self.current.is_alphabetic() {
+                    let identifier = self.makeLetter();
+                    self.tokens.push(identifier);
+                    continue;
+                }
+            }
+
+            if self.current.is_alphabetic() || self.current == '_' {
                 let identifier = self.makeIdentifier();
                 self.tokens.push(identifier);
                 continue;
@@ -343,7 +574,18 @@ impl Tokenizer {
                 continue;
             }
 
-            if "&|!-^<".contains(self.current) {
+            if let Some(tType) = symbolMap.get(&self.current) {
+                let content = match self.current {
+                    '⊤' => "1".to_string(),
+                    '⊥' => "0".to_string(),
+                    _ => self.current.to_string()
+                };
+                self.tokens.push(Token { tType: tType.clone(), content, expression: vec![], start: self.index, end: self.index });
+                self.forward();
+                continue;
+            }
+
+            if "&|!-^<>=".contains(self.current) {
                 let operator = self.makeOperator();
                 self.tokens.push(operator);
                 continue;
@@ -358,71 +600,43 @@ impl Tokenizer {
             self.forward();
         }
 
+        if self.isEngineering() {
+            self.insertImplicitAnd();
+        }
         self.tokens.push(Token { tType: TokenType::EOL, content: "".to_string(), expression: vec![], start: self.index, end: self.index });
     }
 }
 
-pub fn mapToBool(expression: &mut Vec<Token>, idToBool: HashMap<String, String>) {
-    let exp = expression.clone();
-    for (i, token) in exp.iter().enumerate().clone() {
-        if (token.tType == TokenType::IDENTIFIER) && (idToBool.contains_key(&token.content)) {
-            expression[i as usize].tType = TokenType::BOOL;
-            expression[i as usize].content = (*idToBool.get(&token.content).unwrap().clone()).to_string();
-        }
+fn shiftTokens(tokens: &mut Vec<Token>, offset: usize) {
+    for token in tokens {
+        token.start += offset;
+        token.end += offset;
+        shiftTokens(&mut token.expression, offset);
     }
 }
 
-fn getBlock(entryPoint: u16, expression: Vec<Token>) -> (bool, (Token, u16)) {
-    let mut exprStack: Vec<Token> = vec![];
-    let mut i: u16 = entryPoint+1;
-    let exprStart: u16 = expression[entryPoint as usize].start;
-    let mut exprEnd: u16 = 0;
-    let mut open: u16 = 1;
-
-    while i < (expression.len() as u16) {
-        let curr = &expression[i as usize];
-        if open > 0 && curr.tType == TokenType::LPAREN {
-            open += 1;
-            exprStack.push(curr.clone());
-            i += 1;
-            continue;
-        }
-        else if open > 0 && curr.tType == TokenType::RPAREN {
-            open -= 1;
-            if open != 0 {
-                exprStack.push(curr.clone());
-                i += 1;
-                continue;
-            }
-            else {
-                exprEnd = curr.end;
-                return (true, (Token { tType: TokenType::BLOCK, content: "".to_string(), expression: parseBlocks(&mut exprStack), start: exprStart, end: exprEnd }, i));
-            }
-        }
-        else {
-            exprStack.push(curr.clone());
-            i += 1;
-        }
+pub const maxRange: usize = 1024;
+
+// x[1..3] wird zu x[1], x[2], x[3], absteigende Bereiche wie x[3..1] werden rückwärts durchlaufen
+pub fn expandRange(range: &str) -> Option<Vec<String>> {
+    let (name, bounds) = range.strip_suffix(']')?.split_once('[')?;
+    let (from, to) = bounds.split_once("..")?;
+    let from: usize = from.parse().ok()?;
+    let to: usize = to.parse().ok()?;
+    if from.abs_diff(to) >= maxRange {
+        return None;
     }
-    return (false, (Token { tType: TokenType::BLOCK, content: "".to_string(), expression: vec![], start: 0, end: 0 }, 0));
+
+    let indices: Vec<usize> = if from <= to { (from..=to).collect() } else { (to..=from).rev().collect() };
+    Some(indices.iter().map(|index| format!("{}[{}]", name, index)).collect())
 }
 
-pub fn parseBlocks(expression: &mut Vec<Token>) -> Vec<Token> {
-    let mut i: u16 = 0;
-        
-    while i < (expression.len() as u16) {
-        let curr = &expression[i as usize];
-        if curr.tType == TokenType::LPAREN {
-            let result = getBlock(i, expression.to_vec());
-            if result.0 {
-                expression[i as usize] = (result.1).0;
-                for _ in 0..(result.1).1-i {
-                    expression.remove((i+1) as usize);
-                }
-            }
+pub fn getIdentifiers(expression: &Vec<Token>) -> Vec<String> {
+    let mut identifiers: Vec<String> = vec![];
+    for token in expression {
+        if token.tType == TokenType::IDENTIFIER && !identifiers.contains(&token.content) {
+            identifiers.push(token.content.clone());
         }
-        i += 1;
     }
-    return expression.clone();
+    identifiers
 }
-
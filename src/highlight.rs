@@ -0,0 +1,101 @@
+use std::env;
+use crossterm::style::Color;
+use crate::commands;
+use crate::evaluator::tokenizer::{Token, TokenType};
+
+// Darstellung eines Zeichens der Eingabezeile
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct CharStyle {
+    pub colour: Option<Color>,
+    // Erster Syntaxfehler der Zeile
+    pub underline: bool,
+    // Klammer am Cursor und ihr Gegenstück
+    pub bracket: bool
+}
+
+fn colourOf(tType: &TokenType) -> Option<Color> {
+    match tType {
+        TokenType::COMMAND => Some(Color::Magenta),
+        TokenType::IDENTIFIER | TokenType::RANGE => Some(Color::Green),
+        TokenType::BOOL => Some(Color::Cyan),
+        TokenType::LPAREN | TokenType::RPAREN | TokenType::EXPRESSION => Some(Color::Blue),
+        TokenType::ERROR => Some(Color::Red),
+        TokenType::EOL => None,
+        _ => Some(Color::Yellow)
+    }
+}
+
+fn colourTokens(tokens: &Vec<Token>, chars: &[char], styles: &mut Vec<CharStyle>) {
+    for token in tokens {
+        let colour = colourOf(&token.tType);
+        let (start, end) = (token.start, token.end);
+        if token.tType == TokenType::EXPRESSION {
+            // Nur die eckigen Klammern, der Inhalt hat eigene Tokens
+            for position in [start, end] {
+                if chars.get(position).is_some_and(|c| *c == '[' || *c == ']') {
+                    styles[position].colour = colour;
+                }
+            }
+            colourTokens(&token.expression, chars, styles);
+            continue;
+        }
+        for style in styles.iter_mut().take(end + 1).skip(start) {
+            style.colour = colour;
+        }
+    }
+}
+
+// Das Gegenstück zur Klammer an position, gezählt wird nur die gleiche Klammerart
+fn matchingBracket(chars: &[char], position: usize) -> Option<usize> {
+    let (open, close, forward) = match chars[position] {
+        '(' => ('(', ')', true),
+        ')' => ('(', ')', false),
+        '[' => ('[', ']', true),
+        ']' => ('[', ']', false),
+        _ => { return None; }
+    };
+    let mut depth = 0;
+    let positions: Box<dyn Iterator<Item = usize>> = if forward { Box::new(position..chars.len()) } else { Box::new((0..=position).rev()) };
+    for i in positions {
+        if chars[i] == open {
+            depth += if forward { 1 } else { -1 };
+        } else if chars[i] == close {
+            depth += if forward { -1 } else { 1 };
+        }
+        if depth == 0 {
+            return Some(i);
+        }
+    }
+    None
+}
+
+// Ein Eintrag je Zeichen und einer für die Stelle hinter dem letzten Zeichen, an der z.B. ein
+// fehlender Operand gemeldet wird. Mit NO_COLOR bleibt die Zeile unverändert
+pub fn highlight(line: &str, cursor: usize) -> Vec<CharStyle> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut styles = vec![CharStyle::default(); chars.len() + 1];
+    if env::var_os("NO_COLOR").is_some() || line.trim().is_empty() {
+        return styles;
+    }
+
+    let (tokenizer, errors) = commands::analyzeLine(line);
+    colourTokens(&tokenizer.tokens, &chars, &mut styles);
+
+    if let Some(error) = errors.first() {
+        let end = error.end.min(chars.len());
+        for style in styles.iter_mut().take(end + 1).skip(error.start) {
+            style.underline = true;
+        }
+    }
+
+    // Die Klammer unter dem Cursor, sonst die direkt davor
+    let candidates = [Some(cursor), cursor.checked_sub(1)];
+    for position in candidates.into_iter().flatten().filter(|position| *position < chars.len()) {
+        if let Some(partner) = matchingBracket(&chars, position) {
+            styles[position].bracket = true;
+            styles[partner].bracket = true;
+            break;
+        }
+    }
+    styles
+}
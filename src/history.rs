@@ -0,0 +1,134 @@
+use std::{env, fs, path::PathBuf};
+
+static defaultLimit: usize = 1000;
+
+// Bisherige Eingaben, die älteste zuerst. position zeigt beim Blättern mit ↑/↓ auf den gerade
+// angezeigten Eintrag und steht sonst hinter dem letzten
+pub struct History {
+    entries: Vec<String>,
+    position: usize,
+    // Die angefangene Zeile vor dem Blättern, damit ↓ wieder dorthin zurückkommt
+    draft: String,
+    limit: usize,
+    path: Option<PathBuf>
+}
+
+// Laufende Rückwärtssuche mit Strg+R. index ist der gefundene Eintrag
+pub struct Search {
+    pub query: String,
+    pub index: Option<usize>,
+    pub original: String
+}
+
+// $XDG_DATA_HOME bzw. ~/.local/share, unter Windows %APPDATA%
+fn dataDirectory() -> Option<PathBuf> {
+    if let Some(directory) = env::var_os("XDG_DATA_HOME").filter(|directory| !directory.is_empty()) {
+        return Some(PathBuf::from(directory));
+    }
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(PathBuf::from);
+    }
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+}
+
+impl History {
+    // Die Größe lässt sich über LOGICCLI_HISTORY_SIZE festlegen, 0 schaltet die Historie ab
+    pub fn load() -> History {
+        let limit = env::var("LOGICCLI_HISTORY_SIZE").ok()
+            .and_then(|size| size.trim().parse().ok())
+            .unwrap_or(defaultLimit);
+        let path = dataDirectory().map(|directory| directory.join("logicCLI").join("history"));
+
+        let mut history = History { entries: vec![], position: 0, draft: String::new(), limit, path };
+        if limit > 0 {
+            if let Some(content) = history.path.as_ref().and_then(|path| fs::read_to_string(path).ok()) {
+                for line in content.lines() {
+                    history.push(line);
+                }
+            }
+        }
+        history.position = history.entries.len();
+        history
+    }
+
+    // Gleiche Zeilen stehen nur einmal in der Historie, an der Stelle der letzten Eingabe
+    fn push(&mut self, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != line);
+        self.entries.push(line.to_string());
+        if self.entries.len() > self.limit {
+            let excess = self.entries.len() - self.limit;
+            self.entries.drain(..excess);
+        }
+    }
+
+    pub fn add(&mut self, line: &str) {
+        self.push(line);
+        self.position = self.entries.len();
+        self.draft.clear();
+        self.save();
+    }
+
+    // Fehler beim Speichern werden ignoriert, die Historie ist nur eine Erleichterung
+    fn save(&self) {
+        if self.limit == 0 {
+            return;
+        }
+        if let Some(path) = &self.path {
+            if let Some(directory) = path.parent() {
+                let _ = fs::create_dir_all(directory);
+            }
+            let mut content = self.entries.join("\n");
+            content.push('\n');
+            let _ = fs::write(path, content);
+        }
+    }
+
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        if self.position == 0 {
+            return None;
+        }
+        if self.position == self.entries.len() {
+            self.draft = current.to_string();
+        }
+        self.position -= 1;
+        Some(&self.entries[self.position])
+    }
+
+    pub fn newer(&mut self) -> Option<&str> {
+        if self.position >= self.entries.len() {
+            return None;
+        }
+        self.position += 1;
+        if self.position == self.entries.len() {
+            Some(&self.draft)
+        } else {
+            Some(&self.entries[self.position])
+        }
+    }
+
+    pub fn get(&self, index: usize) -> &str {
+        &self.entries[index]
+    }
+
+    // Sucht den neuesten Eintrag vor before, der query enthält
+    pub fn find(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())].iter().rposition(|entry| entry.contains(query))
+    }
+}
+
+impl Search {
+    pub fn new(original: String) -> Search {
+        Search { query: String::new(), index: None, original }
+    }
+
+    pub fn prompt(&self) -> String {
+        if self.index.is_none() && !self.query.is_empty() {
+            format!("(Rückwärtssuche, nicht gefunden) '{}': ", self.query)
+        } else {
+            format!("(Rückwärtssuche) '{}': ", self.query)
+        }
+    }
+}
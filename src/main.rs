@@ -1,13 +1,23 @@
-use std::io::{self, stdout, Write};
+#![allow(non_snake_case, non_upper_case_globals)]
+#![allow(clippy::needless_return, clippy::upper_case_acronyms, clippy::ptr_arg)]
+
+use std::io::{self, Write};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent},
-    execute, terminal::{enable_raw_mode, disable_raw_mode, Clear, ClearType},
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags, PushKeyboardEnhancementFlags, PopKeyboardEnhancementFlags},
+    execute, terminal::{enable_raw_mode, disable_raw_mode, supports_keyboard_enhancement, Clear, ClearType},
     cursor,
 };
 use crate::evaluator::tokenizer::Tokenizer;
+use crate::completion::Completer;
+use crate::editor::LineEditor;
+use crate::history::{History, Search};
 
 mod commands;
+mod completion;
+mod editor;
 mod evaluator;
+mod highlight;
+mod history;
 
 
 fn main() -> io::Result<()> {
@@ -16,8 +26,14 @@ fn main() -> io::Result<()> {
 
     execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
 
+    // Nur so melden manche Terminals Shift+Enter getrennt von Enter
+    let enhanced = supports_keyboard_enhancement().unwrap_or(false);
+    if enhanced {
+        execute!(stdout, PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES))?;
+    }
+
     fn draw_prompt(stdout: &mut impl Write) -> io::Result<()> {
-        write!(stdout, "\rλ> ")?;
+        write!(stdout, "\r{}", editor::prompt)?;
         stdout.flush()?;
         Ok(())
     }
@@ -28,15 +44,85 @@ fn main() -> io::Result<()> {
 
     draw_prompt(&mut stdout)?;
 
-    let mut input = String::new();
+    let mut editor = LineEditor::new();
+    let mut history = History::load();
+    let mut search: Option<Search> = None;
+    let mut completer = Completer::new();
+    // Bereits eingegebene Zeilen einer mehrzeiligen Eingabe
+    let mut pending: Vec<String> = vec![];
 
     loop {
         if event::poll(std::time::Duration::from_millis(500))? {
-            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
-                match code {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Release {
+                    continue;
+                }
+                let control = key.modifiers.contains(KeyModifiers::CONTROL);
+
+                // Während der Rückwärtssuche verändern Zeichen die Suche. Esc bricht ab, andere Tasten
+                // übernehmen den gefundenen Eintrag in die Zeile und wirken dann normal
+                if let Some(mut active) = search.take() {
+                    let searching = match key.code {
+                        KeyCode::Char('r') if control => {
+                            let before = active.index.unwrap_or(usize::MAX);
+                            active.index = history.find(&active.query, before).or(active.index);
+                            true
+                        }
+                        KeyCode::Char(c) if !control => {
+                            active.query.push(c);
+                            active.index = history.find(&active.query, usize::MAX);
+                            true
+                        }
+                        KeyCode::Backspace => {
+                            active.query.pop();
+                            active.index = history.find(&active.query, usize::MAX).filter(|_| !active.query.is_empty());
+                            true
+                        }
+                        _ => false
+                    };
+                    if searching {
+                        if let Some(index) = active.index {
+                            editor.set(history.get(index));
+                        }
+                        editor.setPrompt(&active.prompt());
+                        editor.render(&mut stdout)?;
+                        search = Some(active);
+                        continue;
+                    }
+
+                    editor.setPrompt(editor::prompt);
+                    if key.code == KeyCode::Esc || (control && key.code == KeyCode::Char('g')) {
+                        editor.set(&active.original);
+                        editor.render(&mut stdout)?;
+                        continue;
+                    }
+                    editor.render(&mut stdout)?;
+                }
+
+                match key.code {
                     KeyCode::Enter => {
+                        let mut line = editor.text();
+                        editor.clear();
+                        // Offene Klammern, Shift+Enter oder ein \ am Zeilenende setzen die Eingabe fort
+                        let forced = key.modifiers.contains(KeyModifiers::SHIFT) || line.ends_with('\\');
+                        if line.ends_with('\\') {
+                            line.pop();
+                        }
+                        pending.push(line);
+                        let input = pending.join(" ");
+                        if forced || editor::isIncomplete(&input) {
+                            write!(stdout, "\r\n")?;
+                            editor.setPrompt(editor::continuationPrompt);
+                            editor.setContext(&format!("{} ", input));
+                            editor.render(&mut stdout)?;
+                            continue;
+                        }
+                        pending.clear();
+                        editor.setPrompt(editor::prompt);
+                        editor.setContext("");
+                        history.add(&input);
                         if input.is_empty() {
-                            write!(stdout, "\n")?;
+                            writeln!(stdout)?;
                             draw_prompt(&mut stdout)?;
                             continue;
                         }
@@ -46,63 +132,93 @@ fn main() -> io::Result<()> {
                             }
                             "clear" => {
                                 execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0,0))?;
-                                input.clear();
                                 stdout.flush()?;
                                 draw_prompt(&mut stdout)?;
                             }
                             "help" => {
-                                input.clear();
                                 stdout.flush()?;
                                 write!(stdout, "\r\n")?;
                                 write!(stdout, "{}", commands::commandHelp())?;
-                                write!(stdout, "\n")?;
+                                writeln!(stdout)?;
                                 stdout.flush()?;
                                 draw_prompt(&mut stdout)?;
                             }
                             _ => {
-                                let mut tokenizer = Tokenizer { 
-                                    line: input.clone(),
-                                    index: 0,
-                                    tokens: vec![],
-                                    current: '\0'
-                                };
-
+                                let mut tokenizer = Tokenizer::new(input);
                                 tokenizer.tokenize();
+                                completer.learn(&tokenizer.tokens);
                         
                                 write!(stdout, "\r\n")?;
                                 stdout.flush()?;
-                                write!(stdout,"{}",commands::commandFinder(&tokenizer.tokens))?;
-                                write!(stdout, "\n")?;
+                                write!(stdout,"{}",commands::commandFinder(&tokenizer))?;
+                                writeln!(stdout)?;
                                 stdout.flush()?;
 
-                                input.clear();
                                 draw_prompt(&mut stdout)?;
                             }
                         } 
                     }
+                    // Esc verwirft eine angefangene mehrzeilige Eingabe und beendet sonst das Programm
+                    KeyCode::Esc if !pending.is_empty() => {
+                        pending.clear();
+                        editor.clear();
+                        editor.setPrompt(editor::prompt);
+                        editor.setContext("");
+                        write!(stdout, "\r\n")?;
+                        editor.render(&mut stdout)?;
+                    }
                     KeyCode::Esc => {
                         break;
                     }
-                    KeyCode::Backspace => {
-                        if !input.is_empty() {
-                            input.pop();
-                            execute!(stdout, Clear(ClearType::CurrentLine))?;
-                            draw_prompt(&mut stdout)?;
-                            write!(stdout, "{}", input)?;
-                            stdout.flush()?;
+                    KeyCode::Char('r') if control => {
+                        let active = Search::new(editor.text());
+                        editor.setPrompt(&active.prompt());
+                        editor.render(&mut stdout)?;
+                        search = Some(active);
+                    }
+                    // Ein eindeutiger Kandidat wird eingesetzt, sonst der gemeinsame Anfang. Geht es
+                    // nicht weiter, werden die Kandidaten unter der Zeile aufgelistet
+                    KeyCode::Tab => {
+                        let (start, prefix) = completion::findPrefix(&editor.text(), editor.position());
+                        let candidates = completer.candidates(&editor.text(), start, &prefix);
+                        if candidates.len() == 1 {
+                            let separator = if editor.text().chars().take(start).all(char::is_whitespace) { " " } else { "" };
+                            editor.replaceWord(start, &format!("{}{}", candidates[0], separator));
+                        } else if candidates.len() > 1 {
+                            let common = completion::commonPrefix(&candidates);
+                            if common.chars().count() > prefix.chars().count() {
+                                editor.replaceWord(start, &common);
+                            } else {
+                                write!(stdout, "\r\n{}\r\n", candidates.join("  "))?;
+                            }
+                        }
+                        editor.render(&mut stdout)?;
+                    }
+                    KeyCode::Up => {
+                        if let Some(entry) = history.older(&editor.text()) {
+                            editor.set(entry);
+                            editor.render(&mut stdout)?;
                         }
                     }
-                    KeyCode::Char(c) => {
-                        input.push(c);
-                        write!(stdout, "{}", c)?;
-                        stdout.flush()?;
+                    KeyCode::Down => {
+                        if let Some(entry) = history.newer() {
+                            editor.set(entry);
+                            editor.render(&mut stdout)?;
+                        }
+                    }
+                    _ => {
+                        if editor.handleKey(key) {
+                            editor.render(&mut stdout)?;
+                        }
                     }
-                    _ => {}
                 }
             }
         }
     }
 
+    if enhanced {
+        execute!(stdout, PopKeyboardEnhancementFlags)?;
+    }
     disable_raw_mode()?;
     Ok(())
 }